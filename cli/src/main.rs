@@ -1,7 +1,8 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 use clap::{arg, command, value_parser};
 use rusty_dl::prelude::*;
+use tracing::Level;
 use url::Url;
 
 fn main() -> Result<(), DownloadError> {
@@ -17,57 +18,112 @@ fn main() -> Result<(), DownloadError> {
                 .value_parser(value_parser!(PathBuf)),
         )
         .arg(arg!(-n --name <name> "The name of the downloaded file"))
+        .arg(
+            arg!(-o --output <TEMPLATE> "The template of the paths of the downloaded files, e.g. `{uploader}/{title}.{ext}`")
+                .value_parser(OutputTemplate::parse),
+        )
         .arg(arg!(-s --status "Print progress information on the downloading"))
+        .arg(arg!(--"write-info-json" "Write the metadata of each downloaded file to a `.info.json` file next to it"))
+        .arg(
+            arg!(--overwrite <POLICY> "What to do when a file to download already exists")
+                .value_parser(["overwrite", "skip", "skip-same-size", "rename", "error"])
+                .default_value("overwrite"),
+        )
+        .arg(
+            arg!(--filenames <MODE> "The rules the names of the downloaded files must follow")
+                .value_parser(["ascii", "windows", "posix"])
+                .default_value("windows"),
+        )
+        .arg(arg!(--proxy <URL> "The proxy to send all requests through, starting with http://, https://, socks5:// or socks5h://"))
+        .arg(
+            arg!(--"proxy-user" <CREDENTIALS> "The credentials of the proxy, as `username:password`")
+                .requires("proxy"),
+        )
+        .arg(
+            arg!(--"no-proxy" <HOSTS> "A comma-separated list of hosts to reach without the proxy")
+                .requires("proxy"),
+        )
         .get_matches();
 
     let link = matches.get_one::<Url>("LINK");
     let path = matches.get_one::<PathBuf>("PATH");
     let file_name = matches.get_one::<String>("name");
+    let output_template = matches.get_one::<OutputTemplate>("output");
     let print_status_info = matches.get_one::<bool>("status").unwrap_or(&false);
+    let write_info_json = matches.get_flag("write-info-json");
+    let overwrite_policy = match matches.get_one::<String>("overwrite").map(String::as_str) {
+        Some("skip") => OverwritePolicy::Skip,
+        Some("skip-same-size") => OverwritePolicy::SkipIfSameSize,
+        Some("rename") => OverwritePolicy::Rename,
+        Some("error") => OverwritePolicy::Error,
+        _ => OverwritePolicy::Overwrite,
+    };
+    let sanitize_mode = match matches.get_one::<String>("filenames").map(String::as_str) {
+        Some("ascii") => SanitizeMode::StrictAscii,
+        Some("posix") => SanitizeMode::Posix,
+        _ => SanitizeMode::Windows,
+    };
+    let proxy = matches.get_one::<String>("proxy").map(|url| {
+        let mut proxy = ProxyConfig::new(url);
+
+        if let Some(credentials) = matches.get_one::<String>("proxy-user") {
+            let (username, password) = credentials.split_once(':').unwrap_or((credentials, ""));
+            proxy = proxy.basic_auth(username, password);
+        }
+
+        if let Some(hosts) = matches.get_one::<String>("no-proxy") {
+            proxy = proxy.no_proxy(hosts);
+        }
+
+        proxy
+    });
 
     let url = link.unwrap(/* safe as we set it as required beforehand */);
 
     let rt = tokio::runtime::Runtime::new().expect("tokio runtime cannot be initialized");
 
-    let mut downloader: DownloaderWrapper = match url {
-        link if TwitterDownloader::is_valid_url(url) => {
-            let mut downloader = TwitterDownloader::new(link.as_str()).unwrap();
-
-            if let Some(name) = file_name {
-                downloader.name_all(name.to_owned());
-            }
+    let registry = ExtractorRegistry::new();
+    let mut downloader = registry.downloader(url.as_str())?;
 
-            downloader.into()
-        }
-        link if YoutubeDownloader::is_valid_url(url) => {
-            let mut downloader = YoutubeDownloader::new(link.as_str()).unwrap();
+    if let Some(name) = file_name {
+        set_name(downloader.as_mut(), name.to_owned());
+    }
 
-            if let Some(name) = file_name {
-                downloader.with_name(name.to_owned());
-            }
+    if *print_status_info {
+        tracing_subscriber::fmt()
+            .with_max_level(Level::INFO)
+            .with_target(false)
+            .without_time()
+            .init();
 
-            downloader.into()
-        }
-        link => {
-            let mut downloader = ResourceDownloader::new(link.as_str()).unwrap();
+        downloader.with_progress(ProgressHandler::new(print_progress));
+    }
 
-            if let Some(name) = file_name {
-                downloader.with_name(name.to_owned());
-            }
+    downloader.with_overwrite_policy(overwrite_policy);
+    downloader.with_file_name_sanitizer(FileNameSanitizer::new().mode(sanitize_mode));
+    downloader.with_info_json(write_info_json);
 
-            downloader.into()
-        }
-    };
+    if let Some(template) = output_template {
+        downloader.with_output_template(template.to_owned());
+    }
 
-    if *print_status_info {
-        downloader.print_status_info()
+    if let Some(proxy) = proxy {
+        downloader.with_proxy(proxy)?;
     }
 
-    match path {
+    let report = match path {
         Some(path) => rt.block_on(downloader.download_to(path))?,
         None => rt.block_on(downloader.download())?,
     };
 
+    if *print_status_info {
+        println!(
+            "Downloaded {} file(s), {} bytes in total",
+            report.len(),
+            report.total_size()
+        );
+    }
+
     Ok(())
 }
 
@@ -77,57 +133,41 @@ fn is_valid_download_url(input: &str) -> Result<Url, DownloadError> {
     Ok(url)
 }
 
-#[derive(Clone)]
-pub enum DownloaderWrapper {
-    Rsrc(ResourceDownloader),
-    Yt(YoutubeDownloader),
-    Twi(TwitterDownloader),
-}
-
-impl DownloaderWrapper {
-    pub async fn download(&self) -> Result<(), DownloadError> {
-        match self {
-            DownloaderWrapper::Rsrc(d) => d.download().await,
-            DownloaderWrapper::Yt(d) => d.download().await,
-            DownloaderWrapper::Twi(d) => d.download().await,
-        }
-    }
-
-    pub async fn download_to<P: AsRef<Path> + Send>(&self, path: P) -> Result<(), DownloadError> {
-        match self {
-            DownloaderWrapper::Rsrc(d) => d.download_to(path).await,
-            DownloaderWrapper::Yt(d) => d.download_to(path).await,
-            DownloaderWrapper::Twi(d) => d.download_to(path).await,
-        }
-    }
-
-    pub fn print_status_info(&mut self) {
-        match self {
-            DownloaderWrapper::Rsrc(d) => {
-                d.print_dl_status();
-            }
-            DownloaderWrapper::Yt(d) => {
-                d.print_dl_status();
-            }
-            DownloaderWrapper::Twi(d) => {
-                d.print_dl_status();
-            }
+/// Prints the bytes received by the transfers, their start and their end being printed from the `tracing` events.
+fn print_progress(event: ProgressEvent) {
+    if let ProgressEvent::Progress {
+        item,
+        downloaded,
+        total,
+        speed,
+    } = event
+    {
+        match total {
+            Some(total) if total > 0 => println!(
+                "[{}] {:.1}% ({} / {} bytes, {:.1} KiB/s)",
+                item,
+                downloaded as f64 * 100.0 / total as f64,
+                downloaded,
+                total,
+                speed / 1024.0
+            ),
+            _ => println!(
+                "[{}] {} bytes ({:.1} KiB/s)",
+                item,
+                downloaded,
+                speed / 1024.0
+            ),
         }
     }
 }
 
-impl From<TwitterDownloader> for DownloaderWrapper {
-    fn from(value: TwitterDownloader) -> Self {
-        DownloaderWrapper::Twi(value)
-    }
-}
-impl From<YoutubeDownloader> for DownloaderWrapper {
-    fn from(value: YoutubeDownloader) -> Self {
-        DownloaderWrapper::Yt(value)
-    }
-}
-impl From<ResourceDownloader> for DownloaderWrapper {
-    fn from(value: ResourceDownloader) -> Self {
-        DownloaderWrapper::Rsrc(value)
+/// Sets the name of the downloaded file(s), an option specific to each type of downloader.
+fn set_name(downloader: &mut dyn DynDownloader, name: String) {
+    if let Some(downloader) = downloader.downcast_mut::<TwitterDownloader>() {
+        downloader.name_all(name);
+    } else if let Some(downloader) = downloader.downcast_mut::<YoutubeDownloader>() {
+        downloader.with_name(name);
+    } else if let Some(downloader) = downloader.downcast_mut::<ResourceDownloader>() {
+        downloader.with_name(name);
     }
 }
@@ -1,4 +1,6 @@
-use rusty_dl::{errors::DownloadError, resource::ResourceDownloader, Downloader};
+use rusty_dl::{
+    errors::DownloadError, progress::ProgressEvent, resource::ResourceDownloader, Downloader,
+};
 
 const LINK: &str =
     "https://www.mozilla.org/media/protocol/img/logos/mozilla/logo-word-hor.e20791bb4dd4.svg";
@@ -10,8 +12,16 @@ fn main() -> Result<(), DownloadError> {
     // will download the file with the given name
     downloader.with_name("mozilla_icon.svg".to_owned());
 
-    // keeps track of the download status in the console
-    downloader.print_dl_status();
+    // keeps track of the download progress in the console
+    downloader.on_progress(|event| match event {
+        ProgressEvent::Progress {
+            item, downloaded, ..
+        } => println!("[{}] {} bytes downloaded", item, downloaded),
+        ProgressEvent::Finished { item, path, .. } => {
+            println!("[{}] saved to {}", item, path.display())
+        }
+        _ => {}
+    });
 
     // blocks the main thread to download the given resource
     downloader.blocking_download()?;
@@ -1,4 +1,6 @@
-use rusty_dl::{errors::DownloadError, twitter::TwitterDownloader, Downloader};
+use rusty_dl::{
+    errors::DownloadError, progress::ProgressEvent, twitter::TwitterDownloader, Downloader,
+};
 
 const LINK: &str = "https://twitter.com/user_name/tweet_id";
 
@@ -12,8 +14,16 @@ fn main() -> Result<(), DownloadError> {
     // to download only videos
     downloader.only_videos();
 
-    // keeps track of the download status in the console
-    downloader.print_dl_status();
+    // keeps track of the download progress in the console
+    downloader.on_progress(|event| match event {
+        ProgressEvent::Progress {
+            item, downloaded, ..
+        } => println!("[{}] {} bytes downloaded", item, downloaded),
+        ProgressEvent::Finished { item, path, .. } => {
+            println!("[{}] saved to {}", item, path.display())
+        }
+        _ => {}
+    });
 
     // set a callback to name the generated media files
     // here the files will have as name the index of the file
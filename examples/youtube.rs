@@ -1,5 +1,6 @@
 use rusty_dl::{
     errors::DownloadError,
+    progress::ProgressEvent,
     youtube::{VideoInfo, YoutubeDownloader},
     Downloader,
 };
@@ -22,8 +23,16 @@ fn main() -> Result<(), DownloadError> {
     // downloads only the video and not the audio
     downloader.only_video();
 
-    // keeps track of the download status in the console
-    downloader.print_dl_status();
+    // keeps track of the download progress in the console
+    downloader.on_progress(|event| match event {
+        ProgressEvent::Progress {
+            item, downloaded, ..
+        } => println!("[{}] {} bytes downloaded", item, downloaded),
+        ProgressEvent::Finished { item, path, .. } => {
+            println!("[{}] saved to {}", item, path.display())
+        }
+        _ => {}
+    });
 
     // renames the video with underscores locally
     downloader.rename_with_underscores();
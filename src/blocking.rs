@@ -0,0 +1,44 @@
+use crate::errors::DownloadError;
+use std::future::Future;
+use std::sync::OnceLock;
+use tokio::runtime::{Builder, Handle, Runtime, RuntimeFlavor};
+
+/// Runs the future to completion on the current tokio runtime if any, or else on a runtime shared by all blocking calls.
+pub(crate) fn block_on<T, F: Future<Output = Result<T, DownloadError>>>(
+    future: F,
+) -> Result<T, DownloadError> {
+    let Ok(handle) = Handle::try_current() else {
+        return shared_runtime()?.block_on(future);
+    };
+
+    match handle.runtime_flavor() {
+        // the worker thread is handed over to the other tasks while it is blocked
+        RuntimeFlavor::MultiThread => tokio::task::block_in_place(|| handle.block_on(future)),
+        _ => Err(DownloadError::FailedToBuildBlockingRuntime(
+            "Cannot block a current-thread tokio runtime, use the asynchronous methods instead"
+                .to_owned(),
+        )),
+    }
+}
+
+/// Returns the runtime used by blocking calls made outside of any tokio runtime, creating it on first use.
+fn shared_runtime() -> Result<&'static Runtime, DownloadError> {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+
+    let runtime = Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .map_err(|err| {
+            DownloadError::FailedToBuildBlockingRuntime(format!(
+                "Failed to build blocking runtime: {}",
+                err
+            ))
+        })?;
+
+    // another thread may have created the runtime in the meantime, in which case this one is dropped
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
@@ -0,0 +1,35 @@
+use crate::errors::DownloadError;
+use crate::report::DownloadReport;
+use std::future::Future;
+
+pub use tokio_util::sync::CancellationToken;
+
+/// Runs the future until it completes or the token is cancelled, in which case the future is dropped
+/// and a [`DownloadError::Cancelled`] is returned.
+///
+/// Dropping the future removes the temporary files it was writing to.
+pub(crate) async fn or_cancelled<T, F: Future<Output = Result<T, DownloadError>>>(
+    token: &CancellationToken,
+    future: F,
+) -> Result<T, DownloadError> {
+    token
+        .run_until_cancelled(future)
+        .await
+        .unwrap_or_else(|| Err(cancelled()))
+}
+
+/// Returns a [`DownloadError::Cancelled`] if the token was cancelled, checked before writing each chunk of a transfer.
+pub(crate) fn check(token: &CancellationToken) -> Result<(), DownloadError> {
+    if token.is_cancelled() {
+        return Err(cancelled());
+    }
+
+    Ok(())
+}
+
+/// Returns the error of a cancelled download which did not complete any file.
+fn cancelled() -> DownloadError {
+    DownloadError::Cancelled {
+        completed: DownloadReport::new(),
+    }
+}
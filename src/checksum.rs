@@ -0,0 +1,130 @@
+use crate::errors::DownloadError;
+use sha2::Digest;
+use std::{fmt, path::Path};
+use tokio::io::AsyncReadExt;
+
+/// An expected digest of a downloaded file, given as a hexadecimal string.
+///
+/// The digest is computed while the data is being downloaded, and the download fails with a
+/// [`DownloadError::ChecksumMismatch`] if it does not match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Checksum {
+    /// A SHA-256 digest.
+    Sha256(String),
+    /// A SHA-1 digest.
+    Sha1(String),
+    /// A MD5 digest.
+    Md5(String),
+    /// A BLAKE3 digest.
+    Blake3(String),
+}
+
+impl Checksum {
+    /// Returns the name of the algorithm of the checksum.
+    pub fn algorithm(&self) -> &'static str {
+        match self {
+            Checksum::Sha256(_) => "SHA-256",
+            Checksum::Sha1(_) => "SHA-1",
+            Checksum::Md5(_) => "MD5",
+            Checksum::Blake3(_) => "BLAKE3",
+        }
+    }
+
+    /// Returns the expected digest, in lowercase hexadecimal.
+    pub fn expected(&self) -> String {
+        let (Checksum::Sha256(digest)
+        | Checksum::Sha1(digest)
+        | Checksum::Md5(digest)
+        | Checksum::Blake3(digest)) = self;
+
+        digest.trim().to_ascii_lowercase()
+    }
+
+    /// Creates a new hasher computing the digest of the algorithm of this checksum.
+    pub(crate) fn hasher(&self) -> Hasher {
+        match self {
+            Checksum::Sha256(_) => Hasher::Sha256(sha2::Sha256::new()),
+            Checksum::Sha1(_) => Hasher::Sha1(sha1::Sha1::new()),
+            Checksum::Md5(_) => Hasher::Md5(md5::Md5::new()),
+            Checksum::Blake3(_) => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    /// Compares the digest computed by `hasher` to the expected one.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`DownloadError::ChecksumMismatch`] if the digests differ.
+    pub(crate) fn verify(&self, hasher: Hasher, url: &str) -> Result<(), DownloadError> {
+        let expected = self.expected();
+        let actual = hasher.finalize();
+
+        if expected != actual {
+            return Err(DownloadError::ChecksumMismatch {
+                algorithm: self.algorithm(),
+                expected,
+                actual,
+                url: url.to_owned(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm(), self.expected())
+    }
+}
+
+/// Computes a digest incrementally as the data of a file comes in.
+#[derive(Clone)]
+pub(crate) enum Hasher {
+    Sha256(sha2::Sha256),
+    Sha1(sha1::Sha1),
+    Md5(md5::Md5),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    /// Feeds data to the hasher.
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Sha1(hasher) => hasher.update(data),
+            Hasher::Md5(hasher) => hasher.update(data),
+            Hasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    /// Feeds the content of the file at `path` to the hasher.
+    pub(crate) async fn update_from_file(&mut self, path: &Path) -> Result<(), DownloadError> {
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut buffer = vec![0; 64 * 1024];
+
+        loop {
+            let read = file.read(&mut buffer).await?;
+
+            if read == 0 {
+                return Ok(());
+            }
+
+            self.update(&buffer[..read]);
+        }
+    }
+
+    /// Returns the digest in lowercase hexadecimal.
+    pub(crate) fn finalize(self) -> String {
+        let digest = match self {
+            Hasher::Sha256(hasher) => hasher.finalize().to_vec(),
+            Hasher::Sha1(hasher) => hasher.finalize().to_vec(),
+            Hasher::Md5(hasher) => hasher.finalize().to_vec(),
+            Hasher::Blake3(hasher) => hasher.finalize().as_bytes().to_vec(),
+        };
+
+        digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
@@ -1,40 +1,244 @@
+use crate::report::DownloadReport;
+use reqwest::{Response, StatusCode};
+use std::path::PathBuf;
 use std::{error, fmt};
 
+/// Maximum number of bytes of an error response body kept in [`DownloadError::HttpStatus`].
+const BODY_SNIPPET_LEN: usize = 512;
+
+/// The error of the downloaders.
+///
+/// Besides matching on its variants, the cause of an error can be retrieved with [`DownloadError::kind`],
+/// and [`DownloadError::is_retryable`] tells whether trying again later may succeed.
+/// The errors returned by the downloaders are wrapped in a [`DownloadError::Context`] holding the URL and the extractor involved.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::prelude::{Downloader, ErrorKind, YoutubeDownloader};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let downloader = YoutubeDownloader::new("https://www.youtube.com/watch?v=video_id").unwrap();
+///
+///     if let Err(err) = downloader.download().await {
+///         match err.kind() {
+///             ErrorKind::NotFound | ErrorKind::Private => println!("Skipping `{}`", err.url().unwrap_or_default()),
+///             _ if err.is_retryable() => println!("Retrying later: {}", err),
+///             _ => eprintln!("Failed: {}", err),
+///         }
+///     }
+/// }
+/// ```
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum DownloadError {
     Reqwest(reqwest::Error),
+    /// The server answered with a non-success status code.
+    HttpStatus {
+        /// The status code of the response.
+        status: StatusCode,
+        /// The URL that was requested.
+        url: String,
+        /// The beginning of the response body, truncated to a few hundred bytes.
+        body: String,
+    },
+    /// The digest of a downloaded file does not match the expected one.
+    ChecksumMismatch {
+        /// The name of the algorithm used to compute the digests.
+        algorithm: &'static str,
+        /// The expected digest, in lowercase hexadecimal.
+        expected: String,
+        /// The digest of the downloaded data, in lowercase hexadecimal.
+        actual: String,
+        /// The URL the file was downloaded from.
+        url: String,
+    },
+    /// The download was cancelled through its [`CancellationToken`](crate::cancel::CancellationToken).
+    Cancelled {
+        /// The files that were completely downloaded before the cancellation.
+        completed: DownloadReport,
+    },
+    /// Some items of a playlist or of a tweet failed to download while others were saved.
+    ///
+    /// Its kind and URL are the ones of the first failure.
+    Partial {
+        /// The files that were completely downloaded.
+        completed: DownloadReport,
+        /// The first error which occurred.
+        error: Box<DownloadError>,
+    },
+    /// The file a download would be saved to already exists, see [`OverwritePolicy::Error`](crate::overwrite::OverwritePolicy::Error).
+    FileExists {
+        /// The path of the existing file.
+        path: PathBuf,
+    },
     IoError(std::io::Error),
     ParseError(url::ParseError),
     InvalidUrl(String),
-    VideoNotFound(String),
+    InvalidTemplate(String),
     FailedToBuildBlockingRuntime(String),
-    Downloader(String),
-
-    // #[cfg(feature = "twitter")]
-    TwitterError(String),
+    /// An extractor failed to retrieve some content, e.g. a tweet without media or a page that cannot be parsed.
+    Extractor {
+        /// The cause of the error.
+        kind: ErrorKind,
+        /// A description of the error.
+        message: String,
+    },
+    /// An error which occurred while downloading a URL, see [`DownloadError::with_context`].
+    Context {
+        /// The name of the extractor handling the URL, such as `youtube`.
+        extractor: String,
+        /// The URL of the downloader.
+        url: String,
+        /// The error that occurred.
+        error: Box<DownloadError>,
+    },
 
-    // #[cfg(feature = "youtube")]
+    #[cfg(feature = "youtube")]
     Video(rusty_ytdl::VideoError),
-    // #[cfg(feature = "youtube")]
-    YoutubeError(String),
+}
+
+/// The cause of a [`DownloadError`], returned by [`DownloadError::kind`].
+///
+/// New kinds may be added in future versions, so matches on it must have a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The content does not exist or was removed.
+    NotFound,
+    /// The content is private.
+    Private,
+    /// The content is not available in the country of the client.
+    GeoBlocked,
+    /// Too many requests were sent to the site.
+    RateLimited,
+    /// The content requires the client to be authenticated.
+    AuthRequired,
+    /// The server answered with a status code not covered by the other kinds.
+    HttpStatus(StatusCode),
+    /// The connection failed, timed out or was interrupted.
+    Network,
+    /// The link is not a valid URL, or is not supported by the downloader.
+    InvalidUrl,
+    /// An option of the downloader is invalid, such as its output template or its proxy.
+    InvalidInput,
+    /// The server does not support a feature the download relies on, such as range requests.
+    Unsupported,
+    /// The response of the site could not be understood, e.g. after a change of its pages.
+    Extraction,
+    /// The digest of the downloaded file does not match the expected one.
+    ChecksumMismatch,
+    /// The download was cancelled.
+    Cancelled,
+    /// The file to download already exists.
+    FileExists,
+    /// Reading or writing a local file failed.
+    Io,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorKind {
+    /// Returns the kind of the errors caused by a response with the given status.
+    pub fn from_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::PROXY_AUTHENTICATION_REQUIRED => {
+                ErrorKind::AuthRequired
+            }
+            StatusCode::NOT_FOUND | StatusCode::GONE => ErrorKind::NotFound,
+            StatusCode::TOO_MANY_REQUESTS => ErrorKind::RateLimited,
+            StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS => ErrorKind::GeoBlocked,
+            status => ErrorKind::HttpStatus(status),
+        }
+    }
+
+    /// Returns `true` if an operation failing with this kind of error may succeed when tried again later:
+    /// rate limiting, network errors, and request timeouts or server errors.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ErrorKind::RateLimited | ErrorKind::Network => true,
+            ErrorKind::HttpStatus(status) => {
+                status.is_server_error() || *status == StatusCode::REQUEST_TIMEOUT
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::NotFound => write!(f, "not found"),
+            ErrorKind::Private => write!(f, "private"),
+            ErrorKind::GeoBlocked => write!(f, "geo-blocked"),
+            ErrorKind::RateLimited => write!(f, "rate limited"),
+            ErrorKind::AuthRequired => write!(f, "authentication required"),
+            ErrorKind::HttpStatus(status) => write!(f, "HTTP status {}", status),
+            ErrorKind::Network => write!(f, "network error"),
+            ErrorKind::InvalidUrl => write!(f, "invalid URL"),
+            ErrorKind::InvalidInput => write!(f, "invalid input"),
+            ErrorKind::Unsupported => write!(f, "unsupported"),
+            ErrorKind::Extraction => write!(f, "extraction failed"),
+            ErrorKind::ChecksumMismatch => write!(f, "checksum mismatch"),
+            ErrorKind::Cancelled => write!(f, "cancelled"),
+            ErrorKind::FileExists => write!(f, "file exists"),
+            ErrorKind::Io => write!(f, "I/O error"),
+            ErrorKind::Other => write!(f, "other error"),
+        }
+    }
 }
 
 impl fmt::Display for DownloadError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DownloadError::Reqwest(err) => write!(f, "{}", err),
+            DownloadError::HttpStatus { status, url, body } => {
+                write!(f, "Request to `{}` failed with status {}", url, status)?;
+
+                if !body.is_empty() {
+                    write!(f, ": {}", body)?;
+                }
+
+                Ok(())
+            }
+            DownloadError::ChecksumMismatch {
+                algorithm,
+                expected,
+                actual,
+                url,
+            } => write!(
+                f,
+                "{} checksum mismatch for `{}`: expected {}, got {}",
+                algorithm, url, expected, actual
+            ),
+            DownloadError::Cancelled { completed } => write!(
+                f,
+                "The download was cancelled after {} file(s) completed",
+                completed.len()
+            ),
+            DownloadError::Partial { completed, error } => write!(
+                f,
+                "{} file(s) completed before the download failed: {}",
+                completed.len(),
+                error
+            ),
+            DownloadError::FileExists { path } => {
+                write!(f, "The file `{}` already exists", path.display())
+            }
             DownloadError::IoError(err) => write!(f, "{}", err),
             DownloadError::ParseError(err) => write!(f, "{}", err),
             DownloadError::InvalidUrl(err) => write!(f, "{}", err),
-            DownloadError::VideoNotFound(err) => write!(f, "{}", err),
+            DownloadError::InvalidTemplate(err) => write!(f, "{}", err),
             DownloadError::FailedToBuildBlockingRuntime(err) => write!(f, "{}", err),
-            DownloadError::Downloader(err) => write!(f, "{}", err),
-            // #[cfg(feature = "youtube")]
+            DownloadError::Extractor { message, .. } => write!(f, "{}", message),
+            DownloadError::Context {
+                extractor,
+                url,
+                error,
+            } => write!(f, "[{}] `{}`: {}", extractor, url, error),
+            #[cfg(feature = "youtube")]
             DownloadError::Video(err) => write!(f, "{}", err),
-            // #[cfg(feature = "twitter")]
-            DownloadError::TwitterError(err) => write!(f, "{}", err),
-            // #[cfg(feature = "youtube")]
-            DownloadError::YoutubeError(err) => write!(f, "{}", err),
         }
     }
 }
@@ -43,29 +247,173 @@ impl error::Error for DownloadError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
             DownloadError::Reqwest(err) => Some(err),
+            DownloadError::HttpStatus { .. } => None,
+            DownloadError::ChecksumMismatch { .. } => None,
+            DownloadError::Cancelled { .. } => None,
+            DownloadError::Partial { error, .. } => Some(error.as_ref()),
+            DownloadError::FileExists { .. } => None,
             DownloadError::IoError(err) => Some(err),
             DownloadError::ParseError(err) => Some(err),
             DownloadError::InvalidUrl(_) => None,
-            DownloadError::VideoNotFound(_) => None,
+            DownloadError::InvalidTemplate(_) => None,
             DownloadError::FailedToBuildBlockingRuntime(_) => None,
-            DownloadError::Downloader(_) => None,
-            // #[cfg(feature = "youtube")]
+            DownloadError::Extractor { .. } => None,
+            DownloadError::Context { error, .. } => Some(error.as_ref()),
+            #[cfg(feature = "youtube")]
             DownloadError::Video(err) => Some(err),
-            // #[cfg(feature = "twitter")]
-            DownloadError::TwitterError(_) => None,
-            // #[cfg(feature = "youtube")]
-            DownloadError::YoutubeError(_) => None,
         }
     }
 }
 
+impl DownloadError {
+    /// Creates a [`DownloadError::Extractor`] error, e.g. in the downloaders created by a custom
+    /// [`Extractor`](crate::extractor::Extractor).
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        DownloadError::Extractor {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Wraps the error in a [`DownloadError::Context`] holding the extractor and the URL involved.
+    ///
+    /// The context of an error which already has one is replaced, so that it refers to the outermost downloader,
+    /// e.g. to the tweet rather than to one of its medias.
+    pub fn with_context(self, extractor: impl Into<String>, url: impl Into<String>) -> Self {
+        DownloadError::Context {
+            extractor: extractor.into(),
+            url: url.into(),
+            error: Box::new(self.into_inner()),
+        }
+    }
+
+    /// Returns the error without its [`DownloadError::Context`], if it has one.
+    pub fn inner(&self) -> &DownloadError {
+        match self {
+            DownloadError::Context { error, .. } => error,
+            err => err,
+        }
+    }
+
+    /// Consumes the error and returns it without its [`DownloadError::Context`], if it has one.
+    pub fn into_inner(self) -> DownloadError {
+        match self {
+            DownloadError::Context { error, .. } => *error,
+            err => err,
+        }
+    }
+
+    /// Returns the cause of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            DownloadError::Reqwest(err) => reqwest_kind(err),
+            DownloadError::HttpStatus { status, .. } => ErrorKind::from_status(*status),
+            DownloadError::ChecksumMismatch { .. } => ErrorKind::ChecksumMismatch,
+            DownloadError::Cancelled { .. } => ErrorKind::Cancelled,
+            DownloadError::Partial { error, .. } => error.kind(),
+            DownloadError::FileExists { .. } => ErrorKind::FileExists,
+            DownloadError::IoError(_) => ErrorKind::Io,
+            DownloadError::ParseError(_) | DownloadError::InvalidUrl(_) => ErrorKind::InvalidUrl,
+            DownloadError::InvalidTemplate(_) => ErrorKind::InvalidInput,
+            DownloadError::FailedToBuildBlockingRuntime(_) => ErrorKind::Other,
+            DownloadError::Extractor { kind, .. } => *kind,
+            DownloadError::Context { error, .. } => error.kind(),
+            #[cfg(feature = "youtube")]
+            DownloadError::Video(err) => video_kind(err),
+        }
+    }
+
+    /// Returns `true` if trying the download again later may succeed, see [`ErrorKind::is_retryable`].
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+
+    /// Returns the URL involved in the error: the one of its context, or else the one of the failed request.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            DownloadError::Context { url, .. } => Some(url),
+            DownloadError::HttpStatus { url, .. } | DownloadError::ChecksumMismatch { url, .. } => {
+                Some(url)
+            }
+            DownloadError::Partial { error, .. } => error.url(),
+            DownloadError::Reqwest(err) => err.url().map(|url| url.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the extractor involved in the error, if it has a context.
+    pub fn extractor(&self) -> Option<&str> {
+        match self {
+            DownloadError::Context { extractor, .. } => Some(extractor),
+            _ => None,
+        }
+    }
+
+    /// Returns the response if its status is a success, or else a [`DownloadError::HttpStatus`] holding the beginning of its body.
+    pub(crate) async fn check_status(mut response: Response) -> Result<Response, DownloadError> {
+        let status = response.status();
+
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let url = response.url().to_string();
+        let mut snippet = Vec::new();
+
+        while snippet.len() < BODY_SNIPPET_LEN {
+            match response.chunk().await {
+                Ok(Some(chunk)) => snippet.extend_from_slice(&chunk),
+                _ => break,
+            }
+        }
+
+        let mut body = String::from_utf8_lossy(&snippet).trim().to_owned();
+
+        if body.len() > BODY_SNIPPET_LEN {
+            let mut end = BODY_SNIPPET_LEN;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            body.truncate(end);
+            body.push('…');
+        }
+
+        Err(DownloadError::HttpStatus { status, url, body })
+    }
+}
+
+/// Returns the kind of a [`reqwest::Error`], from the status of the response if it has one.
+fn reqwest_kind(err: &reqwest::Error) -> ErrorKind {
+    match err.status() {
+        Some(status) => ErrorKind::from_status(status),
+        None if err.is_builder() => ErrorKind::InvalidInput,
+        None => ErrorKind::Network,
+    }
+}
+
+/// Returns the kind of a [`rusty_ytdl::VideoError`].
+#[cfg(feature = "youtube")]
+fn video_kind(err: &rusty_ytdl::VideoError) -> ErrorKind {
+    use rusty_ytdl::VideoError;
+
+    match err {
+        VideoError::VideoNotFound | VideoError::VideoSourceNotFound => ErrorKind::NotFound,
+        VideoError::VideoIsPrivate => ErrorKind::Private,
+        VideoError::Reqwest(err) => reqwest_kind(err),
+        VideoError::URLParseError(_) => ErrorKind::InvalidUrl,
+        VideoError::BodyCannotParsed => ErrorKind::Extraction,
+        _ => ErrorKind::Other,
+    }
+}
+
 impl From<reqwest::Error> for DownloadError {
     fn from(value: reqwest::Error) -> Self {
         Self::Reqwest(value)
     }
 }
 
-// #[cfg(feature = "youtube")]
+#[cfg(feature = "youtube")]
 impl From<rusty_ytdl::VideoError> for DownloadError {
     fn from(value: rusty_ytdl::VideoError) -> Self {
         Self::Video(value)
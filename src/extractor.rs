@@ -0,0 +1,455 @@
+use crate::cancel::CancellationToken;
+use crate::errors::DownloadError;
+use crate::http::{HttpConfig, ProxyConfig, Transport};
+use crate::info::MediaInfo;
+use crate::overwrite::OverwritePolicy;
+use crate::progress::ProgressHandler;
+use crate::report::DownloadReport;
+#[cfg(feature = "resource")]
+use crate::resource::{self, ResourceDownloader};
+use crate::retry::RetryPolicy;
+use crate::sanitize::FileNameSanitizer;
+use crate::template::OutputTemplate;
+#[cfg(feature = "twitter")]
+use crate::twitter::{self, TwitterDownloader, TwitterEndpoints};
+#[cfg(feature = "youtube")]
+use crate::youtube::{self, YoutubeDownloader, YoutubeEndpoints};
+use crate::Downloader;
+use reqwest::Client;
+use std::any::Any;
+use std::path::Path;
+use url::Url;
+
+/// Object-safe counterpart of [`Downloader`], so that downloaders of different types can be stored and used
+/// behind a `Box<dyn DynDownloader>`, such as the ones created by an [`ExtractorRegistry`].
+///
+/// Any [`Downloader`] is turned into one with [`Downloader::boxed`]. The settings specific to a type of downloader
+/// are reached by downcasting it with [`downcast_mut`](#method.downcast_mut).
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::prelude::{DownloadError, ExtractorRegistry, OverwritePolicy, YoutubeDownloader};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), DownloadError> {
+///     let registry = ExtractorRegistry::new();
+///     let mut downloader = registry.downloader("https://www.youtube.com/watch?v=video_id")?;
+///
+///     downloader.with_overwrite_policy(OverwritePolicy::Skip);
+///
+///     if let Some(youtube) = downloader.downcast_mut::<YoutubeDownloader>() {
+///         youtube.only_audio();
+///     }
+///
+///     downloader.download_to("./downloads/".as_ref()).await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[async_trait::async_trait]
+pub trait DynDownloader: Send + Sync {
+    /// See [`Downloader::download`].
+    async fn download(&self) -> Result<DownloadReport, DownloadError>;
+
+    /// See [`Downloader::download_to`].
+    async fn download_to(&self, folder_path: &Path) -> Result<DownloadReport, DownloadError>;
+
+    /// See [`Downloader::info`].
+    async fn info(&self) -> Result<MediaInfo, DownloadError>;
+
+    /// See [`Downloader::with_progress`].
+    fn with_progress(&mut self, handler: ProgressHandler) -> &mut dyn DynDownloader;
+
+    /// See [`Downloader::with_part_suffix`].
+    fn with_part_suffix(&mut self, suffix: &str) -> &mut dyn DynDownloader;
+
+    /// See [`Downloader::with_retry_policy`].
+    fn with_retry_policy(&mut self, policy: RetryPolicy) -> &mut dyn DynDownloader;
+
+    /// See [`Downloader::with_client`].
+    fn with_client(&mut self, client: Client) -> &mut dyn DynDownloader;
+
+    /// See [`Downloader::with_http_config`].
+    fn with_http_config(
+        &mut self,
+        config: &HttpConfig,
+    ) -> Result<&mut dyn DynDownloader, DownloadError>;
+
+    /// See [`Downloader::with_proxy`].
+    fn with_proxy(&mut self, proxy: ProxyConfig) -> Result<&mut dyn DynDownloader, DownloadError>;
+
+    /// See [`Downloader::with_transport`].
+    fn with_transport(&mut self, transport: Transport) -> &mut dyn DynDownloader;
+
+    /// See [`Downloader::with_output_template`].
+    fn with_output_template(&mut self, template: OutputTemplate) -> &mut dyn DynDownloader;
+
+    /// See [`Downloader::with_overwrite_policy`].
+    fn with_overwrite_policy(&mut self, policy: OverwritePolicy) -> &mut dyn DynDownloader;
+
+    /// See [`Downloader::with_cancellation`].
+    fn with_cancellation(&mut self, token: CancellationToken) -> &mut dyn DynDownloader;
+
+    /// See [`Downloader::with_file_name_sanitizer`].
+    fn with_file_name_sanitizer(&mut self, sanitizer: FileNameSanitizer) -> &mut dyn DynDownloader;
+
+    /// See [`Downloader::with_info_json`].
+    fn with_info_json(&mut self, enabled: bool) -> &mut dyn DynDownloader;
+
+    /// Returns the underlying downloader, to be downcast to its concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns the underlying downloader mutably, to be downcast to its concrete type.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl dyn DynDownloader + '_ {
+    /// Returns the underlying downloader if it is a `T`.
+    pub fn downcast_ref<T: Downloader + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    /// Returns the underlying downloader mutably if it is a `T`, e.g. to set the options specific to its type.
+    pub fn downcast_mut<T: Downloader + 'static>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut()
+    }
+}
+
+/// Wrapper implementing [`DynDownloader`] for a [`Downloader`].
+///
+/// The downloaders themselves do not implement it, so that calling one of their methods is never ambiguous.
+pub(crate) struct Boxed<D>(pub(crate) D);
+
+#[async_trait::async_trait]
+impl<D: Downloader + Send + Sync + 'static> DynDownloader for Boxed<D> {
+    async fn download(&self) -> Result<DownloadReport, DownloadError> {
+        self.0.download().await
+    }
+
+    async fn download_to(&self, folder_path: &Path) -> Result<DownloadReport, DownloadError> {
+        self.0.download_to(folder_path).await
+    }
+
+    async fn info(&self) -> Result<MediaInfo, DownloadError> {
+        self.0.info().await
+    }
+
+    fn with_progress(&mut self, handler: ProgressHandler) -> &mut dyn DynDownloader {
+        self.0.with_progress(handler);
+        self
+    }
+
+    fn with_part_suffix(&mut self, suffix: &str) -> &mut dyn DynDownloader {
+        self.0.with_part_suffix(suffix);
+        self
+    }
+
+    fn with_retry_policy(&mut self, policy: RetryPolicy) -> &mut dyn DynDownloader {
+        self.0.with_retry_policy(policy);
+        self
+    }
+
+    fn with_client(&mut self, client: Client) -> &mut dyn DynDownloader {
+        self.0.with_client(client);
+        self
+    }
+
+    fn with_http_config(
+        &mut self,
+        config: &HttpConfig,
+    ) -> Result<&mut dyn DynDownloader, DownloadError> {
+        self.0.with_http_config(config)?;
+        Ok(self)
+    }
+
+    fn with_proxy(&mut self, proxy: ProxyConfig) -> Result<&mut dyn DynDownloader, DownloadError> {
+        self.0.with_proxy(proxy)?;
+        Ok(self)
+    }
+
+    fn with_transport(&mut self, transport: Transport) -> &mut dyn DynDownloader {
+        self.0.with_transport(transport);
+        self
+    }
+
+    fn with_output_template(&mut self, template: OutputTemplate) -> &mut dyn DynDownloader {
+        self.0.with_output_template(template);
+        self
+    }
+
+    fn with_overwrite_policy(&mut self, policy: OverwritePolicy) -> &mut dyn DynDownloader {
+        self.0.with_overwrite_policy(policy);
+        self
+    }
+
+    fn with_cancellation(&mut self, token: CancellationToken) -> &mut dyn DynDownloader {
+        self.0.with_cancellation(token);
+        self
+    }
+
+    fn with_file_name_sanitizer(&mut self, sanitizer: FileNameSanitizer) -> &mut dyn DynDownloader {
+        self.0.with_file_name_sanitizer(sanitizer);
+        self
+    }
+
+    fn with_info_json(&mut self, enabled: bool) -> &mut dyn DynDownloader {
+        self.0.with_info_json(enabled);
+        self
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        &self.0
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        &mut self.0
+    }
+}
+
+/// Creates the downloaders of the URLs of a site, to be registered in an [`ExtractorRegistry`].
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::prelude::{DownloadError, Downloader, DynDownloader, Extractor, ExtractorRegistry, ResourceDownloader};
+/// use url::Url;
+///
+/// /// Downloads the files of `files.example.com` through their direct link.
+/// struct ExampleExtractor;
+///
+/// impl Extractor for ExampleExtractor {
+///     fn name(&self) -> &str {
+///         "example"
+///     }
+///
+///     fn priority(&self) -> i32 {
+///         10
+///     }
+///
+///     fn matches(&self, url: &Url) -> bool {
+///         url.host_str() == Some("files.example.com")
+///     }
+///
+///     fn create(&self, url: &Url) -> Result<Box<dyn DynDownloader>, DownloadError> {
+///         let direct_link = format!("https://cdn.example.com{}", url.path());
+///
+///         Ok(ResourceDownloader::new(&direct_link)?.boxed())
+///     }
+/// }
+///
+/// let mut registry = ExtractorRegistry::new();
+/// registry.register(ExampleExtractor);
+/// ```
+pub trait Extractor: Send + Sync {
+    /// Returns the name of the extractor, e.g. `youtube`.
+    fn name(&self) -> &str;
+
+    /// Returns the priority of the extractor. Extractors with a higher priority are tried first. Defaults to `0`.
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// Checks if the extractor handles the URL.
+    fn matches(&self, url: &Url) -> bool;
+
+    /// Creates the downloader of the URL.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`DownloadError`] if the URL cannot be downloaded by the extractor.
+    fn create(&self, url: &Url) -> Result<Box<dyn DynDownloader>, DownloadError>;
+}
+
+/// Extractor of YouTube videos and playlists, creating [`YoutubeDownloader`]s.
+#[cfg(feature = "youtube")]
+#[derive(Debug, Clone, Default)]
+pub struct YoutubeExtractor {
+    endpoints: YoutubeEndpoints,
+}
+
+#[cfg(feature = "youtube")]
+impl YoutubeExtractor {
+    /// Creates an extractor whose downloaders scrape the pages at the given endpoints, e.g. of a mirror.
+    pub fn with_endpoints(endpoints: YoutubeEndpoints) -> Self {
+        Self { endpoints }
+    }
+}
+
+#[cfg(feature = "youtube")]
+impl Extractor for YoutubeExtractor {
+    fn name(&self) -> &str {
+        youtube::EXTRACTOR
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        YoutubeDownloader::is_valid_url(url)
+    }
+
+    fn create(&self, url: &Url) -> Result<Box<dyn DynDownloader>, DownloadError> {
+        let mut downloader = YoutubeDownloader::new(url.as_str())?;
+        downloader.with_endpoints(self.endpoints.clone());
+
+        Ok(downloader.boxed())
+    }
+}
+
+/// Extractor of the medias of tweets, creating [`TwitterDownloader`]s.
+#[cfg(feature = "twitter")]
+#[derive(Debug, Clone, Default)]
+pub struct TwitterExtractor {
+    endpoints: TwitterEndpoints,
+}
+
+#[cfg(feature = "twitter")]
+impl TwitterExtractor {
+    /// Creates an extractor whose downloaders send their requests to the given endpoints, e.g. of a mirror.
+    pub fn with_endpoints(endpoints: TwitterEndpoints) -> Self {
+        Self { endpoints }
+    }
+}
+
+#[cfg(feature = "twitter")]
+impl Extractor for TwitterExtractor {
+    fn name(&self) -> &str {
+        twitter::EXTRACTOR
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        TwitterDownloader::is_valid_url(url)
+    }
+
+    fn create(&self, url: &Url) -> Result<Box<dyn DynDownloader>, DownloadError> {
+        let mut downloader = TwitterDownloader::new(url.as_str())?;
+        downloader.with_endpoints(self.endpoints.clone());
+
+        Ok(downloader.boxed())
+    }
+}
+
+/// Extractor of any file served over HTTP(S), creating [`ResourceDownloader`]s. It is the default fallback.
+#[cfg(feature = "resource")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResourceExtractor;
+
+#[cfg(feature = "resource")]
+impl Extractor for ResourceExtractor {
+    fn name(&self) -> &str {
+        resource::EXTRACTOR
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        ResourceDownloader::is_valid_url(url)
+    }
+
+    fn create(&self, url: &Url) -> Result<Box<dyn DynDownloader>, DownloadError> {
+        Ok(ResourceDownloader::new(url.as_str())?.boxed())
+    }
+}
+
+/// Registry of the [`Extractor`]s creating the downloader of a URL.
+///
+/// Extractors are tried by decreasing priority, in the order they were registered for equal priorities.
+/// The fallback is used when none of them handles the URL.
+/// [`ExtractorRegistry::new`] registers the extractors of the crate enabled by its features,
+/// with `ResourceExtractor` as fallback if the `resource` feature is enabled.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::prelude::{DownloadError, ExtractorRegistry};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), DownloadError> {
+///     let registry = ExtractorRegistry::new();
+///
+///     for link in ["https://www.youtube.com/watch?v=video_id", "https://example.com/file.zip"] {
+///         registry.downloader(link)?.download().await?;
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub struct ExtractorRegistry {
+    extractors: Vec<Box<dyn Extractor>>,
+    fallback: Option<Box<dyn Extractor>>,
+}
+
+impl Default for ExtractorRegistry {
+    #[allow(unused_mut)]
+    fn default() -> Self {
+        let mut registry = Self::empty();
+
+        #[cfg(feature = "youtube")]
+        registry.register(YoutubeExtractor::default());
+
+        #[cfg(feature = "twitter")]
+        registry.register(TwitterExtractor::default());
+
+        #[cfg(feature = "resource")]
+        registry.with_fallback(ResourceExtractor);
+
+        registry
+    }
+}
+
+impl ExtractorRegistry {
+    /// Creates a registry of the extractors of the crate enabled by its features, with `ResourceExtractor` as fallback.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry without any extractor nor fallback.
+    pub fn empty() -> Self {
+        Self {
+            extractors: Vec::new(),
+            fallback: None,
+        }
+    }
+
+    /// Registers an extractor, tried after the ones of higher or equal priority already registered.
+    pub fn register<E: Extractor + 'static>(&mut self, extractor: E) -> &mut Self {
+        let index = self
+            .extractors
+            .partition_point(|registered| registered.priority() >= extractor.priority());
+        self.extractors.insert(index, Box::new(extractor));
+
+        self
+    }
+
+    /// Sets the extractor used when no registered extractor handles a URL.
+    pub fn with_fallback<E: Extractor + 'static>(&mut self, extractor: E) -> &mut Self {
+        self.fallback = Some(Box::new(extractor));
+
+        self
+    }
+
+    /// Returns the registered extractors in the order they are tried, without the fallback.
+    pub fn extractors(&self) -> impl Iterator<Item = &dyn Extractor> {
+        self.extractors.iter().map(|extractor| extractor.as_ref())
+    }
+
+    /// Returns the extractor handling the URL, the fallback if none of the registered ones does.
+    pub fn find(&self, url: &Url) -> Option<&dyn Extractor> {
+        self.extractors()
+            .chain(self.fallback.as_deref())
+            .find(|extractor| extractor.matches(url))
+    }
+
+    /// Creates the downloader of the link with the extractor handling it.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`DownloadError::InvalidUrl`] if the link is not a valid URL or if no extractor handles it,
+    /// or the error of the extractor if it fails to create the downloader.
+    pub fn downloader(&self, link: &str) -> Result<Box<dyn DynDownloader>, DownloadError> {
+        let url = Url::parse(link)
+            .map_err(|_| DownloadError::InvalidUrl(format!("Invalid URL `{}`.", link)))?;
+
+        let extractor = self.find(&url).ok_or_else(|| {
+            DownloadError::InvalidUrl(format!("No extractor handles the URL `{}`.", link))
+        })?;
+
+        extractor.create(&url)
+    }
+}
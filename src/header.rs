@@ -103,6 +103,16 @@ impl HeaderMapBuilder {
         self.field(header::PRAGMA, user_agent_value)
     }
 
+    /// Adds a 'Range' header with the specified value.
+    pub fn range<V: Into<HeaderBuilderValue>>(self, value: V) -> Self {
+        self.field(header::RANGE, value)
+    }
+
+    /// Adds an 'If-Range' header with the specified value.
+    pub fn if_range<V: Into<HeaderBuilderValue>>(self, value: V) -> Self {
+        self.field(header::IF_RANGE, value)
+    }
+
     /// Adds a 'Content-Disposition' header with the specified value.
     pub fn content_disposition<V: Into<HeaderBuilderValue>>(self, user_agent_value: V) -> Self {
         self.field(header::CONTENT_DISPOSITION, user_agent_value)
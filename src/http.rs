@@ -0,0 +1,313 @@
+use crate::errors::DownloadError;
+use reqwest::{Certificate, Client, NoProxy, Proxy, Request, Response};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Configuration of the HTTP client shared by all the requests of a downloader.
+///
+/// Use [`Downloader::with_http_config`](crate::Downloader::with_http_config) to build a client from it,
+/// or [`Downloader::with_client`](crate::Downloader::with_client) to provide a [`Client`] built by yourself.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::http::HttpConfig;
+/// use rusty_dl::prelude::{Downloader, ResourceDownloader};
+/// use std::time::Duration;
+///
+/// let config = HttpConfig::new()
+///     .timeout(Duration::from_secs(60))
+///     .connect_timeout(Duration::from_secs(10));
+///
+/// let mut downloader = ResourceDownloader::new("https://example.com/file.zip").unwrap();
+/// downloader.with_http_config(&config).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HttpConfig {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    root_certificates: Vec<Certificate>,
+    accept_invalid_certs: bool,
+    proxy: Option<ProxyConfig>,
+}
+
+impl HttpConfig {
+    /// Creates a new [`HttpConfig`] with the default settings of [`reqwest`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the timeout of whole requests, from connecting to reading the end of the response body.
+    ///
+    /// As it includes the transfer of the body, it should be generous when downloading large files.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout of the connection phase only.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the maximum duration to wait for data to be received while reading a response.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Adds a certificate to the trusted root certificates, e.g. the one of a corporate TLS inspection proxy.
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Disables the validation of TLS certificates.
+    ///
+    /// **This is dangerous**: any certificate is then trusted, which makes requests vulnerable to man-in-the-middle attacks.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// Sends all the requests through the given proxy.
+    ///
+    /// When no proxy is set, the proxies defined by the `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` environment variables are used.
+    pub fn proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Returns the proxy the requests are sent through, if any.
+    pub fn get_proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_ref()
+    }
+
+    /// Builds a [`Client`] with this configuration.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`DownloadError::Reqwest`] if the TLS backend cannot be initialized,
+    /// or a [`DownloadError::InvalidUrl`] if the URL of the proxy is invalid.
+    pub fn build_client(&self) -> Result<Client, DownloadError> {
+        let mut builder = Client::builder().danger_accept_invalid_certs(self.accept_invalid_certs);
+
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+
+        if let Some(timeout) = self.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.to_proxy()?);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+/// Proxy the requests of a downloader are sent through.
+///
+/// HTTP, HTTPS and SOCKS5 proxies are supported, the kind of proxy being given by the scheme of its URL:
+/// `http://`, `https://`, `socks5://` or `socks5h://` (the latter resolving host names through the proxy).
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::http::ProxyConfig;
+/// use rusty_dl::prelude::{Downloader, YoutubeDownloader};
+///
+/// let proxy = ProxyConfig::new("socks5h://proxy.corp.example:1080")
+///     .basic_auth("user", "password")
+///     .no_proxy("localhost,127.0.0.1,.corp.example");
+///
+/// let mut downloader = YoutubeDownloader::new("https://www.youtube.com/watch?v=video_id").unwrap();
+/// downloader.with_proxy(proxy).unwrap();
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    url: String,
+    credentials: Option<(String, String)>,
+    no_proxy: Option<String>,
+}
+
+impl ProxyConfig {
+    /// Schemes of the supported proxies.
+    const SCHEMES: [&'static str; 4] = ["http", "https", "socks5", "socks5h"];
+
+    /// Creates a new [`ProxyConfig`] for the proxy at the given URL, e.g. `http://proxy.example:8080`.
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            credentials: None,
+            no_proxy: None,
+        }
+    }
+
+    /// Sets the credentials sent to the proxy using basic authentication.
+    pub fn basic_auth(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some((username.to_owned(), password.to_owned()));
+        self
+    }
+
+    /// Sets the hosts which are reached directly instead of through the proxy, as a comma-separated list.
+    ///
+    /// Entries may be host names (also matching their subdomains when starting with a `.`), IP addresses or CIDR blocks,
+    /// e.g. `localhost,.corp.example,10.0.0.0/8`.
+    pub fn no_proxy(mut self, hosts: &str) -> Self {
+        self.no_proxy = Some(hosts.to_owned());
+        self
+    }
+
+    /// Returns the URL of the proxy.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Converts the configuration into a [`Proxy`] applying to all requests.
+    pub(crate) fn to_proxy(&self) -> Result<Proxy, DownloadError> {
+        let url = url::Url::parse(&self.url)
+            .map_err(|_| DownloadError::InvalidUrl(format!("Invalid proxy URL: `{}`", self.url)))?;
+
+        if !Self::SCHEMES.contains(&url.scheme()) || !url.has_host() {
+            return Err(DownloadError::InvalidUrl(format!(
+                "Invalid proxy URL: `{}`. The URL must start with 'http://', 'https://', 'socks5://' or 'socks5h://' and include a host.",
+                self.url
+            )));
+        }
+
+        let mut proxy = Proxy::all(url)?;
+
+        if let Some((username, password)) = &self.credentials {
+            proxy = proxy.basic_auth(username, password);
+        }
+
+        if let Some(hosts) = &self.no_proxy {
+            proxy = proxy.no_proxy(NoProxy::from_string(hosts));
+        }
+
+        Ok(proxy)
+    }
+}
+
+impl fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the password must not end up in logs
+        f.debug_struct("ProxyConfig")
+            .field("url", &self.url)
+            .field(
+                "credentials",
+                &self
+                    .credentials
+                    .as_ref()
+                    .map(|(username, _)| (username, "***")),
+            )
+            .field("no_proxy", &self.no_proxy)
+            .finish()
+    }
+}
+
+/// Sends the HTTP requests of a downloader, see [`Downloader::with_transport`](crate::Downloader::with_transport).
+///
+/// By default the requests are sent by the [`Client`] of the downloader, which implements this trait.
+/// A custom transport can serve recorded responses for offline tests, or wrap a client to instrument the requests.
+/// Responses can be built from an `http::Response` with [`Response::from`].
+///
+/// The requests sent by [`rusty_ytdl`](https://docs.rs/rusty_ytdl) to retrieve the information and the streams of YouTube videos
+/// can only be sent by a [`Client`]: they are sent by the one returned by [`HttpTransport::client`] if any,
+/// by the client of the downloader otherwise.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use reqwest::{Client, Request, Response};
+/// use rusty_dl::http::{HttpTransport, Transport};
+/// use rusty_dl::prelude::{DownloadError, Downloader, ResourceDownloader};
+///
+/// /// Logs the requests before sending them with a client.
+/// struct LoggingTransport(Client);
+///
+/// #[async_trait::async_trait]
+/// impl HttpTransport for LoggingTransport {
+///     async fn execute(&self, request: Request) -> Result<Response, DownloadError> {
+///         println!("{} {}", request.method(), request.url());
+///
+///         Ok(self.0.execute(request).await?)
+///     }
+/// }
+///
+/// let mut downloader = ResourceDownloader::new("https://example.com/file.zip").unwrap();
+/// downloader.with_transport(Transport::new(LoggingTransport(Client::new())));
+/// ```
+#[async_trait::async_trait]
+pub trait HttpTransport: Send + Sync {
+    /// Sends the request and returns its response, whatever its status.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`DownloadError`] if no response is received. The errors whose kind is
+    /// [`ErrorKind::Network`](crate::errors::ErrorKind::Network) are retried according to the
+    /// [`RetryPolicy`](crate::retry::RetryPolicy) of the downloader.
+    async fn execute(&self, request: Request) -> Result<Response, DownloadError>;
+
+    /// Returns the client the transport sends the requests with, if any.
+    ///
+    /// It sends the requests which cannot go through [`HttpTransport::execute`], such as the ones of
+    /// [`rusty_ytdl`](https://docs.rs/rusty_ytdl) for YouTube videos. Defaults to `None`.
+    fn client(&self) -> Option<&Client> {
+        None
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for Client {
+    async fn execute(&self, request: Request) -> Result<Response, DownloadError> {
+        Ok(Client::execute(self, request).await?)
+    }
+
+    fn client(&self) -> Option<&Client> {
+        Some(self)
+    }
+}
+
+/// Shared [`HttpTransport`], cheap to clone, set on downloaders with
+/// [`Downloader::with_transport`](crate::Downloader::with_transport).
+#[derive(Clone)]
+pub struct Transport(Arc<dyn HttpTransport>);
+
+impl Transport {
+    /// Creates a shared transport from the given one.
+    pub fn new<T: HttpTransport + 'static>(transport: T) -> Self {
+        Self(Arc::new(transport))
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for Transport {
+    async fn execute(&self, request: Request) -> Result<Response, DownloadError> {
+        self.0.execute(request).await
+    }
+
+    fn client(&self) -> Option<&Client> {
+        self.0.client()
+    }
+}
+
+impl fmt::Debug for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transport").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,153 @@
+use crate::errors::DownloadError;
+use crate::report::{DownloadedFile, FileKind};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Metadata of the content behind a link, retrieved without downloading it with [`Downloader::info`](crate::Downloader::info).
+///
+/// Fields the site does not provide are `None` or empty. It can be serialized, e.g. to JSON with `serde_json`.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::prelude::{DownloadError, Downloader, YoutubeDownloader};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), DownloadError> {
+///     let downloader = YoutubeDownloader::new("https://www.youtube.com/watch?v=video_id").unwrap();
+///     let info = downloader.info().await?;
+///
+///     println!("{} by {}", info.title.unwrap_or_default(), info.uploader.unwrap_or_default());
+///
+///     for format in info.media.iter().flat_map(|media| &media.formats) {
+///         println!("{:?} {:?} bytes", format.mime_type, format.size);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaInfo {
+    /// The identifier of the content on its site, e.g. the ID of a video or of a tweet.
+    pub id: Option<String>,
+    /// The URL of the content.
+    pub url: String,
+    /// The title of the content, or the text of a tweet.
+    pub title: Option<String>,
+    /// The name of the author of the content.
+    pub uploader: Option<String>,
+    /// The date the content was published or last modified, as `YYYYMMDD`.
+    pub upload_date: Option<String>,
+    /// The playback duration in seconds.
+    pub duration: Option<f64>,
+    /// The description of the content.
+    pub description: Option<String>,
+    /// The thumbnails of the content.
+    pub thumbnails: Vec<Thumbnail>,
+    /// The medias that can be downloaded, such as the video of a YouTube link or the images of a tweet.
+    pub media: Vec<Media>,
+    /// The items of a collection such as a playlist, each with its own information.
+    pub entries: Vec<MediaInfo>,
+}
+
+/// A thumbnail of some content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Thumbnail {
+    /// The URL of the image.
+    pub url: String,
+    /// The width of the image in pixels.
+    pub width: Option<u64>,
+    /// The height of the image in pixels.
+    pub height: Option<u64>,
+}
+
+/// A media that can be downloaded, available in one or several formats.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Media {
+    /// The kind of the media.
+    pub kind: FileKind,
+    /// The formats the media is available in.
+    pub formats: Vec<MediaFormat>,
+}
+
+/// A format a media is available in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaFormat {
+    /// The identifier of the format on its site, such as the `itag` of YouTube formats.
+    pub format_id: Option<String>,
+    /// The URL the format is downloaded from.
+    pub url: String,
+    /// The kind of the format, e.g. [`FileKind::Audio`] for the audio-only formats of a video.
+    pub kind: FileKind,
+    /// The extension of the file.
+    pub ext: Option<String>,
+    /// The MIME type of the file, possibly with its codecs.
+    pub mime_type: Option<String>,
+    /// The width of the image or video in pixels.
+    pub width: Option<u64>,
+    /// The height of the image or video in pixels.
+    pub height: Option<u64>,
+    /// The bitrate in bits per second.
+    pub bitrate: Option<u64>,
+    /// The size of the file in bytes.
+    pub size: Option<u64>,
+}
+
+/// Metadata of a downloaded file, written as JSON next to it when enabled with
+/// [`Downloader::with_info_json`](crate::Downloader::with_info_json).
+///
+/// The sidecar of `video.mp4` is `video.info.json`, see [`InfoJson::path_for`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InfoJson {
+    /// The URL of the downloaded item, e.g. the link of the video or of the tweet.
+    pub url: String,
+    /// The name of the extractor which downloaded the file: `youtube`, `twitter` or `resource`.
+    pub extractor: String,
+    /// The identifier of the item on its site.
+    pub id: Option<String>,
+    /// The title of the item, or the text of a tweet.
+    pub title: Option<String>,
+    /// The name of the author of the item.
+    pub uploader: Option<String>,
+    /// The date the item was published or last modified, as `YYYYMMDD`.
+    pub upload_date: Option<String>,
+    /// The time the download completed, in seconds since the Unix epoch.
+    pub downloaded_at: u64,
+    /// The name of the downloaded file.
+    pub file_name: String,
+    /// The size of the downloaded file in bytes.
+    pub size: u64,
+    /// The format that was downloaded.
+    pub format: Option<MediaFormat>,
+    /// The metadata specific to the site, such as the `VideoDetails` of a YouTube video,
+    /// or the text and the counts of a tweet.
+    pub details: serde_json::Value,
+}
+
+impl InfoJson {
+    /// Returns the path of the sidecar of the file at `path`, its extension being replaced by `info.json`.
+    pub fn path_for(path: &Path) -> PathBuf {
+        path.with_extension("info.json")
+    }
+
+    /// Writes the sidecar of the downloaded file and returns its path.
+    pub(crate) async fn write(mut self, file: &DownloadedFile) -> Result<PathBuf, DownloadError> {
+        self.downloaded_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        self.file_name = file
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.size = file.size;
+
+        let path = Self::path_for(&file.path);
+        let json = serde_json::to_vec_pretty(&self).map_err(std::io::Error::from)?;
+        tokio::fs::write(&path, json).await?;
+
+        Ok(path)
+    }
+}
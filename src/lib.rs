@@ -18,7 +18,10 @@
 //!
 //! fn main() -> Result<(), DownloadError>  {
 //!     let downloader = YoutubeDownloader::new(URL).expect("INVALID URL");
-//!     downloader.blocking_download()
+//!     let report = downloader.blocking_download()?;
+//!
+//!     println!("{} file(s) downloaded", report.len());
+//!     Ok(())
 //! }
 //! ```
 //!
@@ -35,8 +38,9 @@
 //! #[tokio::main]
 //! async fn main() -> Result<(), DownloadError> {
 //!     let downloader = YoutubeDownloader::new(URL).expect("INVALID URL");
+//!     downloader.download().await?;
 //!
-//!     downloader.download().await
+//!     Ok(())
 //! }
 //! ```
 //!
@@ -45,19 +49,70 @@
 //! These methods can be used with any type that implements the `Downloader` trait.
 //! May that be `TwitterDownloader`, `YoutubeDownloader` or `ResourceDownloader`.
 //!
+//! ### Features
+//!
+//! Each downloader is behind a cargo feature, all of them being enabled by default through the `full` feature:
+//! - `youtube`: [`YoutubeDownloader`](youtube::YoutubeDownloader), pulling in `rusty_ytdl` and `scraper`
+//! - `twitter`: [`TwitterDownloader`](twitter::TwitterDownloader), which enables `resource`
+//! - `resource`: [`ResourceDownloader`](resource::ResourceDownloader) and the [`Checksum`](checksum::Checksum) of its files,
+//!   pulling in the hashing crates
+//!
+//! Downloading plain files only requires `default-features = false, features = ["resource"]`.
+//!
+//! ### Tracing
+//!
+//! With the `tracing` feature, the downloads and their HTTP requests are instrumented with
+//! [`tracing`](https://docs.rs/tracing) spans carrying the extractor, the URL and the item index of the downloads,
+//! along with the bytes written and the time elapsed once each file is complete.
+//! The crate prints nothing itself, install a subscriber such as `tracing-subscriber` to display them.
+//!
 //! See more in [github's examples directory](https://github.com/DevYatsu/rusty-dl/examples/)
+
+// without any downloader, the helpers they share are all unused
+#![cfg_attr(
+    not(any(feature = "resource", feature = "youtube")),
+    allow(dead_code, unused_imports)
+)]
+
+mod blocking;
+pub mod cancel;
+#[cfg(feature = "resource")]
+pub mod checksum;
 pub mod errors;
+pub mod extractor;
 pub mod header;
+pub mod http;
+pub mod info;
+pub mod overwrite;
+pub mod part_file;
+pub mod progress;
+pub mod report;
+pub mod retry;
+pub mod sanitize;
+pub mod template;
+mod trace;
 
-// #[cfg(feature = "resource")]
+#[cfg(feature = "resource")]
 pub mod resource;
-// #[cfg(feature = "twitter")]
+#[cfg(feature = "twitter")]
 pub mod twitter;
 
-// #[cfg(feature = "youtube")]
+#[cfg(feature = "youtube")]
 pub mod youtube;
 
+use crate::cancel::CancellationToken;
 use crate::errors::DownloadError;
+use crate::extractor::DynDownloader;
+use crate::http::{HttpConfig, ProxyConfig, Transport};
+use crate::info::MediaInfo;
+use crate::overwrite::OverwritePolicy;
+use crate::part_file::DEFAULT_PART_SUFFIX;
+use crate::progress::{ProgressEvent, ProgressHandler};
+use crate::report::DownloadReport;
+use crate::retry::RetryPolicy;
+use crate::sanitize::FileNameSanitizer;
+use crate::template::OutputTemplate;
+use reqwest::Client;
 use std::{future::Future, path::Path};
 use url::Url;
 
@@ -91,31 +146,283 @@ pub trait Downloader {
     /// Checks if the given URL is a valid Download URL.
     fn is_valid_url(url: &Url) -> bool;
 
-    /// Prints the download status.
+    /// Turns the downloader into a [`DynDownloader`], so that it can be stored along downloaders of other types
+    /// or returned by an [`Extractor`](crate::extractor::Extractor).
+    fn boxed(self) -> Box<dyn DynDownloader>
+    where
+        Self: Sized + Send + Sync + 'static,
+    {
+        Box::new(extractor::Boxed(self))
+    }
+
+    /// Does nothing, the crate no longer prints to the console.
     ///
-    /// This function sets the field print_download_status to `true` and thus allows displaying the current progress of the download to the console.
+    /// The status of the downloads is reported to the handler set with [`Downloader::on_progress`] or [`Downloader::with_progress`],
+    /// and emitted as [`tracing`](https://docs.rs/tracing) events with the `tracing` feature.
+    #[deprecated(
+        note = "use `Downloader::on_progress` to receive structured progress events instead"
+    )]
     fn print_dl_status(&mut self) -> &mut Self {
-        let status = self.get_dl_status();
-        *status = true;
+        self
+    }
+
+    /// Sets the handler receiving the [`ProgressEvent`]s of the downloads: started, received bytes along with the total size
+    /// and the speed, finished and failed, each of them identifying the video of a playlist or the media of a tweet it relates to.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use rusty_dl::prelude::{Downloader, ProgressEvent, ProgressHandler, TwitterDownloader};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let (handler, mut events) = ProgressHandler::channel();
+    ///
+    ///     let mut downloader = TwitterDownloader::new("https://twitter.com/user/status/123456789").unwrap();
+    ///     downloader.with_progress(handler);
+    ///
+    ///     tokio::spawn(async move { downloader.download().await });
+    ///
+    ///     while let Some(event) = events.recv().await {
+    ///         if let ProgressEvent::Finished { item, path, .. } = event {
+    ///             println!("Media {} saved to {}", item, path.display());
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn with_progress(&mut self, handler: ProgressHandler) -> &mut Self {
+        *self.get_progress_handler() = Some(handler);
+
+        self
+    }
+
+    /// Sets a callback called with each [`ProgressEvent`] of the downloads, see [`Downloader::with_progress`].
+    fn on_progress<F: Fn(ProgressEvent) + Send + Sync + 'static>(
+        &mut self,
+        callback: F,
+    ) -> &mut Self {
+        self.with_progress(ProgressHandler::new(callback))
+    }
+
+    /// Gets a mutable reference to the progress handler.
+    fn get_progress_handler(&mut self) -> &mut Option<ProgressHandler>;
+
+    /// Sets the suffix appended to the name of files while they are being downloaded.
+    ///
+    /// Files are first written with this suffix (`.part` by default), synced to the disk and only then renamed to their final name,
+    /// so that a file under its final name is always complete. Temporary files of aborted downloads are removed.
+    /// An empty suffix resets it to the default one.
+    fn with_part_suffix(&mut self, suffix: &str) -> &mut Self {
+        let part_suffix = self.get_part_suffix();
+        *part_suffix = match suffix {
+            "" => DEFAULT_PART_SUFFIX.to_owned(),
+            suffix => suffix.to_owned(),
+        };
+
+        self
+    }
+
+    /// Gets a mutable reference to the suffix of files being downloaded.
+    fn get_part_suffix(&mut self) -> &mut String;
+
+    /// Sets the policy used to retry requests and transfers failing with transient errors.
+    ///
+    /// It applies to every request sent by the downloader, the ones retrieving metadata as well as the media transfers.
+    /// Defaults to [`RetryPolicy::default`], use [`RetryPolicy::never`] to disable retries.
+    fn with_retry_policy(&mut self, policy: RetryPolicy) -> &mut Self {
+        *self.get_retry_policy() = policy;
+
+        self
+    }
+
+    /// Gets a mutable reference to the retry policy.
+    fn get_retry_policy(&mut self) -> &mut RetryPolicy;
+
+    /// Sets the HTTP client used for all the requests of the downloader.
+    ///
+    /// The client is reused for every request so that connections are pooled. It can be shared between several downloaders
+    /// as cloning a [`Client`] is cheap, and configured with proxies, timeouts or custom TLS roots beforehand.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use rusty_dl::prelude::{Downloader, ResourceDownloader};
+    /// use std::time::Duration;
+    ///
+    /// let client = reqwest::Client::builder()
+    ///     .timeout(Duration::from_secs(30))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mut downloader = ResourceDownloader::new("https://example.com/file.zip").unwrap();
+    /// downloader.with_client(client.clone());
+    /// ```
+    fn with_client(&mut self, client: Client) -> &mut Self {
+        *self.get_client() = client;
+
+        self
+    }
+
+    /// Sets the HTTP client used for all the requests of the downloader to one built from the given configuration.
+    ///
+    /// The whole configuration, from the proxy to the timeouts and the certificates, applies to every request sent through the client,
+    /// including the ones sent by [`rusty_ytdl`](https://docs.rs/rusty_ytdl) for YouTube videos.
+    /// It is also kept by the downloader, so that [`Downloader::with_proxy`] rebuilds the client from it.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`DownloadError`] if the client cannot be built, see [`HttpConfig::build_client`].
+    fn with_http_config(&mut self, config: &HttpConfig) -> Result<&mut Self, DownloadError> {
+        *self.get_client() = config.build_client()?;
+        *self.get_http_config() = config.clone();
+
+        Ok(self)
+    }
+
+    /// Sends all the requests of the downloader through the given proxy.
+    ///
+    /// The client of the downloader is rebuilt from its current [`HttpConfig`] with the proxy set,
+    /// replacing any client previously set with [`Downloader::with_client`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`DownloadError`] if the URL of the proxy is invalid or if the client cannot be built.
+    fn with_proxy(&mut self, proxy: ProxyConfig) -> Result<&mut Self, DownloadError> {
+        let config = self.get_http_config().clone().proxy(proxy);
+
+        self.with_http_config(&config)
+    }
+
+    /// Gets a mutable reference to the configuration of the HTTP client.
+    fn get_http_config(&mut self) -> &mut HttpConfig;
+
+    /// Gets a mutable reference to the HTTP client.
+    fn get_client(&mut self) -> &mut Client;
+
+    /// Sends the requests of the downloader through the given transport instead of its client,
+    /// e.g. to serve recorded responses in tests, see [`HttpTransport`](crate::http::HttpTransport).
+    ///
+    /// The client and its configuration are then only used to build the requests, and to send the ones of YouTube videos
+    /// if the transport has no client of its own, see [`HttpTransport::client`](crate::http::HttpTransport::client).
+    fn with_transport(&mut self, transport: Transport) -> &mut Self {
+        *self.get_transport() = Some(transport);
 
         self
     }
 
-    /// Gets a mutable reference to the download status.
+    /// Gets a mutable reference to the transport of the downloader, `None` when the requests are sent by its client.
+    fn get_transport(&mut self) -> &mut Option<Transport>;
+
+    /// Sets the template of the paths the files are saved to, relative to the download folder, see [`OutputTemplate`].
+    ///
+    /// The template takes precedence over the names set with the methods specific to each downloader.
+    /// Subdirectories it contains are created as needed.
+    fn with_output_template(&mut self, template: OutputTemplate) -> &mut Self {
+        *self.get_output_template() = Some(template);
+
+        self
+    }
+
+    /// Gets a mutable reference to the output template.
+    fn get_output_template(&mut self) -> &mut Option<OutputTemplate>;
+
+    /// Sets the policy applied when a file to download already exists, see [`OverwritePolicy`].
     ///
-    /// This function returns a mutable reference to the download status, allowing it to be modified.
-    fn get_dl_status(&mut self) -> &mut bool;
+    /// Defaults to [`OverwritePolicy::Overwrite`]. Skipped files are listed in the [`DownloadReport`] with
+    /// [`DownloadedFile::skipped`](crate::report::DownloadedFile::skipped) set.
+    fn with_overwrite_policy(&mut self, policy: OverwritePolicy) -> &mut Self {
+        *self.get_overwrite_policy() = policy;
 
-    /// Sanitizes the file name
+        self
+    }
+
+    /// Gets a mutable reference to the overwrite policy.
+    fn get_overwrite_policy(&mut self) -> &mut OverwritePolicy;
+
+    /// Sets the token cancelling the downloads.
+    ///
+    /// Once the token is cancelled, the ongoing transfers stop at their next chunk, their temporary files are removed
+    /// and the download returns a [`DownloadError::Cancelled`] listing the files completed beforehand, such as the
+    /// videos of a playlist that were already saved, wrapped in the [`DownloadError::Context`] of the downloader.
+    /// Partial files of resumable downloads are kept so that they can be resumed later on.
+    ///
+    /// The token can be shared between several downloaders, or derived with [`CancellationToken::child_token`]
+    /// to cancel only some of them.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use rusty_dl::prelude::{CancellationToken, DownloadError, Downloader, YoutubeDownloader};
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let token = CancellationToken::new();
+    ///
+    ///     let mut downloader = YoutubeDownloader::new("https://www.youtube.com/playlist?list=playlist_id").unwrap();
+    ///     downloader.with_cancellation(token.clone());
+    ///
+    ///     let download = tokio::spawn(async move { downloader.download().await });
+    ///
+    ///     tokio::time::sleep(Duration::from_secs(60)).await;
+    ///     token.cancel();
+    ///
+    ///     if let Err(DownloadError::Cancelled { completed }) =
+    ///         download.await.unwrap().map_err(DownloadError::into_inner)
+    ///     {
+    ///         println!("{} video(s) downloaded before the cancellation", completed.len());
+    ///     }
+    /// }
+    /// ```
+    fn with_cancellation(&mut self, token: CancellationToken) -> &mut Self {
+        *self.get_cancellation_token() = token;
+
+        self
+    }
+
+    /// Gets a mutable reference to the cancellation token.
+    fn get_cancellation_token(&mut self) -> &mut CancellationToken;
+
+    /// Sets the sanitizer turning titles and names into valid file names, see [`FileNameSanitizer`].
+    ///
+    /// It applies to the names of the downloaded files and to the folders created for playlists or by output templates.
+    /// Defaults to [`FileNameSanitizer::default`], producing names valid on every platform.
+    fn with_file_name_sanitizer(&mut self, sanitizer: FileNameSanitizer) -> &mut Self {
+        *self.get_file_name_sanitizer() = sanitizer;
+
+        self
+    }
+
+    /// Gets a mutable reference to the file name sanitizer.
+    fn get_file_name_sanitizer(&mut self) -> &mut FileNameSanitizer;
+
+    /// Sets whether an [`InfoJson`](crate::info::InfoJson) sidecar is written next to each downloaded file.
+    ///
+    /// The sidecar holds the source URL, the name of the extractor, the identifier, title and author of the item,
+    /// its dates and the downloaded format. Files kept because of the overwrite policy do not get one.
+    /// Defaults to `false`.
+    fn with_info_json(&mut self, enabled: bool) -> &mut Self {
+        *self.get_info_json() = enabled;
+
+        self
+    }
+
+    /// Gets a mutable reference to whether sidecars are written.
+    fn get_info_json(&mut self) -> &mut bool;
+
+    /// Sanitizes the file name with the default [`FileNameSanitizer`].
+    #[deprecated(
+        note = "use `Downloader::with_file_name_sanitizer` or `FileNameSanitizer::sanitize` instead"
+    )]
     fn sanitize_file_name(s: &str) -> String {
-        s.replace("\\", "|").replace("/", "|")
+        FileNameSanitizer::default().sanitize(s)
     }
 
     /// Downloads and saves the file to the current working directory.
     ///
     /// ## Returns
     ///
-    /// Returns a future representing the download operation, which resolves to a [`Result`] indicating success or failure.
+    /// Returns a future representing the download operation, which resolves to a [`DownloadReport`] listing the written files on success.
     ///
     /// ## Examples
     ///
@@ -130,7 +437,7 @@ pub trait Downloader {
     ///     Ok(())
     /// }
     /// ```
-    async fn download(&self) -> Result<(), DownloadError> {
+    async fn download(&self) -> Result<DownloadReport, DownloadError> {
         self.download_to("./").await
     }
 
@@ -142,7 +449,7 @@ pub trait Downloader {
     ///
     /// ## Returns
     ///
-    /// Returns a future representing the download operation, which resolves to a [`Result`] indicating success or failure.
+    /// Returns a future representing the download operation, which resolves to a [`DownloadReport`] listing the written files on success.
     ///
     /// ## Examples
     ///
@@ -160,7 +467,29 @@ pub trait Downloader {
     async fn download_to<P: AsRef<Path> + std::marker::Send>(
         &self,
         folder_path: P,
-    ) -> Result<(), DownloadError>;
+    ) -> Result<DownloadReport, DownloadError>;
+
+    /// Retrieves the metadata of the content behind the URL without downloading it.
+    ///
+    /// ## Returns
+    ///
+    /// Returns a future resolving to the [`MediaInfo`] of the content, listing the medias that can be downloaded and their formats.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use rusty_dl::prelude::{DownloadError, Downloader, TwitterDownloader};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), DownloadError> {
+    ///     let downloader = TwitterDownloader::new("https://twitter.com/user/status/tweet_id").unwrap();
+    ///     let info = downloader.info().await?;
+    ///
+    ///     println!("{} media(s) in the tweet", info.media.len());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn info(&self) -> Result<MediaInfo, DownloadError>;
 
     /// Blocks the current thread until the download completes, using asynchronous execution.
     ///
@@ -168,7 +497,7 @@ pub trait Downloader {
     ///
     /// ## Returns
     ///
-    /// Returns a [`Result`] indicating success or failure of the download operation.
+    /// Returns a [`DownloadReport`] listing the written files on success.
     ///
     /// ## Examples
     ///
@@ -182,7 +511,7 @@ pub trait Downloader {
     ///     Ok(())
     /// }
     /// ```
-    fn blocking_download(&self) -> Result<(), DownloadError>
+    fn blocking_download(&self) -> Result<DownloadReport, DownloadError>
     where
         Self: Sync,
     {
@@ -197,7 +526,7 @@ pub trait Downloader {
     ///
     /// ## Returns
     ///
-    /// Returns a `Result` indicating success or failure of the download operation.
+    /// Returns a [`DownloadReport`] listing the written files on success.
     ///
     /// ## Example
     ///
@@ -214,40 +543,63 @@ pub trait Downloader {
     fn blocking_download_to<P: AsRef<Path> + std::marker::Send>(
         &self,
         path: P,
-    ) -> Result<(), DownloadError>
+    ) -> Result<DownloadReport, DownloadError>
     where
         Self: Sync,
     {
         Self::blocking(async { self.download_to(path).await })
     }
 
-    fn blocking<F: Future>(async_block: F) -> Result<(), DownloadError> {
-        // Create a multi-threaded Tokio runtime with the default number of worker threads
-        let rt = tokio::runtime::Builder::new_multi_thread()
-            .enable_all()
-            .build()
-            .map_err(|_| {
-                DownloadError::FailedToBuildBlockingRuntime(
-                    "Failed to build blocking runtime".to_owned(),
-                )
-            })?;
+    /// Blocks the current thread until the metadata of the content is retrieved, using asynchronous execution.
+    ///
+    /// ## Returns
+    ///
+    /// Returns the [`MediaInfo`] of the content on success.
+    fn blocking_info(&self) -> Result<MediaInfo, DownloadError>
+    where
+        Self: Sync,
+    {
+        Self::blocking(async { self.info().await })
+    }
 
-        // Block the current thread until the download completes
-        rt.block_on(async_block);
-        Ok(())
+    /// Blocks the current thread until the future completes and returns its output.
+    ///
+    /// When called from a multi-threaded tokio runtime, the future is driven by that runtime without blocking its other tasks.
+    /// Otherwise it runs on a runtime shared by all blocking calls, created on first use.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`DownloadError::FailedToBuildBlockingRuntime`] when called from a current-thread tokio runtime,
+    /// which cannot be blocked without deadlocking, or if the shared runtime cannot be created.
+    fn blocking<T, F: Future<Output = Result<T, DownloadError>>>(
+        async_block: F,
+    ) -> Result<T, DownloadError> {
+        blocking::block_on(async_block)
     }
 }
 
 pub mod prelude {
-    pub use crate::errors::DownloadError;
+    pub use crate::cancel::CancellationToken;
+    pub use crate::errors::{DownloadError, ErrorKind};
+    pub use crate::extractor::{DynDownloader, Extractor, ExtractorRegistry};
+    pub use crate::http::{HttpConfig, HttpTransport, ProxyConfig, Transport};
+    pub use crate::info::MediaInfo;
+    pub use crate::overwrite::OverwritePolicy;
+    pub use crate::progress::{ProgressEvent, ProgressHandler};
+    pub use crate::report::{DownloadReport, DownloadedFile, FileKind};
+    pub use crate::retry::RetryPolicy;
+    pub use crate::sanitize::{FileNameSanitizer, SanitizeMode};
+    pub use crate::template::OutputTemplate;
     pub use crate::Downloader;
 
-    // #[cfg(feature = "resource")]
+    #[cfg(feature = "resource")]
+    pub use crate::checksum::Checksum;
+    #[cfg(feature = "resource")]
     pub use crate::resource::ResourceDownloader;
 
-    // #[cfg(feature = "twitter")]
-    pub use crate::twitter::TwitterDownloader;
+    #[cfg(feature = "twitter")]
+    pub use crate::twitter::{TwitterDownloader, TwitterEndpoints};
 
-    // #[cfg(feature = "youtube")]
-    pub use crate::youtube::YoutubeDownloader;
+    #[cfg(feature = "youtube")]
+    pub use crate::youtube::{YoutubeDownloader, YoutubeEndpoints};
 }
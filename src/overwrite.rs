@@ -0,0 +1,142 @@
+use crate::errors::DownloadError;
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The destinations claimed by the downloads in progress, see [`Claim`].
+static CLAIMED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// Policy deciding what happens when a download would be saved to a file that already exists.
+///
+/// It applies to every file of a download, so that re-running the download of a playlist with
+/// [`OverwritePolicy::Skip`] only fetches the videos that are missing.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::prelude::{Downloader, OverwritePolicy, YoutubeDownloader};
+///
+/// let mut downloader = YoutubeDownloader::new("https://www.youtube.com/playlist?list=playlist_id").unwrap();
+/// downloader.with_overwrite_policy(OverwritePolicy::SkipIfSameSize);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OverwritePolicy {
+    /// Replaces the existing file once the download completes. This is the default.
+    #[default]
+    Overwrite,
+    /// Keeps the existing file and does not download it again.
+    Skip,
+    /// Keeps the existing file if its size is the size of the remote file, replaces it otherwise.
+    /// The file is replaced when the server does not advertise the size of the remote file.
+    SkipIfSameSize,
+    /// Saves the download next to the existing file, appending ` (n)` to its name with the lowest `n` available,
+    /// e.g. `video (1).mp4`. The files of a download sharing a name, such as the videos of a playlist with the same title,
+    /// are renamed the same way.
+    Rename,
+    /// Fails with a [`DownloadError::FileExists`].
+    Error,
+}
+
+/// Where a download should be saved, according to the [`OverwritePolicy`].
+#[derive(Debug)]
+pub(crate) enum Destination {
+    /// The download must be saved to this path, which must not be given to another download until the [`Claim`] is dropped.
+    Download(PathBuf, Claim),
+    /// The file at this path, of `size` bytes, is kept and the download skipped.
+    Existing { path: PathBuf, size: u64 },
+}
+
+/// The destination of a download in progress, released when dropped.
+///
+/// Only [`OverwritePolicy::Rename`] claims destinations, so that the downloads running concurrently, such as the videos
+/// of a playlist sharing a title, are not given the same name before any of them is written.
+#[derive(Debug, Default)]
+pub(crate) struct Claim(Option<PathBuf>);
+
+impl Claim {
+    /// Claims `path`, returning `None` if another download in progress already did.
+    fn new(path: &Path) -> Option<Self> {
+        let mut claimed = CLAIMED.lock().unwrap_or_else(|err| err.into_inner());
+
+        claimed
+            .insert(path.to_owned())
+            .then(|| Self(Some(path.to_owned())))
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if let Some(path) = self.0.take() {
+            let mut claimed = CLAIMED.lock().unwrap_or_else(|err| err.into_inner());
+            claimed.remove(&path);
+        }
+    }
+}
+
+impl OverwritePolicy {
+    /// Decides where a download meant to be saved at `path` goes, given the size of the remote file if known.
+    pub(crate) async fn resolve(
+        self,
+        path: &Path,
+        remote_size: Option<u64>,
+    ) -> Result<Destination, DownloadError> {
+        if let OverwritePolicy::Rename = self {
+            let (path, claim) = claim_available_path(path).await?;
+            return Ok(Destination::Download(path, claim));
+        }
+
+        let size = match tokio::fs::metadata(path).await {
+            Ok(metadata) => metadata.len(),
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                return Ok(Destination::Download(path.to_owned(), Claim::default()))
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        match self {
+            OverwritePolicy::Overwrite | OverwritePolicy::Rename => {
+                Ok(Destination::Download(path.to_owned(), Claim::default()))
+            }
+            OverwritePolicy::SkipIfSameSize if remote_size != Some(size) => {
+                Ok(Destination::Download(path.to_owned(), Claim::default()))
+            }
+            OverwritePolicy::Skip | OverwritePolicy::SkipIfSameSize => Ok(Destination::Existing {
+                path: path.to_owned(),
+                size,
+            }),
+            OverwritePolicy::Error => Err(DownloadError::FileExists {
+                path: path.to_owned(),
+            }),
+        }
+    }
+}
+
+/// Claims the first path among `path` and the ones of the form `<stem> (n).<extension>` next to it
+/// that does not exist and is not claimed by another download in progress.
+async fn claim_available_path(path: &Path) -> Result<(PathBuf, Claim), DownloadError> {
+    let stem = path.file_stem().unwrap_or(path.as_os_str());
+    let mut candidate = path.to_owned();
+    let mut n = 1;
+
+    loop {
+        // the claim is only released once the file is written, so a path that is not claimed anymore already exists
+        if let Some(claim) = Claim::new(&candidate) {
+            if !tokio::fs::try_exists(&candidate).await? {
+                return Ok((candidate, claim));
+            }
+        }
+
+        let mut name: OsString = stem.to_owned();
+        name.push(format!(" ({})", n));
+
+        if let Some(extension) = path.extension() {
+            name.push(".");
+            name.push(extension);
+        }
+
+        candidate = path.with_file_name(name);
+        n += 1;
+    }
+}
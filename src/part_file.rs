@@ -0,0 +1,110 @@
+use crate::errors::DownloadError;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use tokio::fs::File;
+#[cfg(feature = "resource")]
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+
+/// Default suffix appended to the name of a file while it is being downloaded.
+pub const DEFAULT_PART_SUFFIX: &str = ".part";
+
+/// A file being downloaded, written next to its final path with a temporary suffix.
+///
+/// The file is only moved to its final path by [`PartFile::commit`], once its content has been synced to the disk.
+/// If it is dropped before that, the temporary file is removed, unless [`PartFile::keep`] was called.
+#[derive(Debug)]
+pub(crate) struct PartFile {
+    path: PathBuf,
+    part_path: PathBuf,
+    file: Option<File>,
+    keep: bool,
+}
+
+impl PartFile {
+    /// Returns the temporary path of the file to be saved at `path`, i.e. `path` followed by `suffix`.
+    pub(crate) fn part_path(path: &Path, suffix: &str) -> PathBuf {
+        let mut part_path: OsString = path.as_os_str().to_owned();
+        part_path.push(suffix);
+
+        PathBuf::from(part_path)
+    }
+
+    /// Creates an empty temporary file for the file to be saved at `path`, truncating any previous one.
+    pub(crate) async fn create(path: &Path, suffix: &str) -> Result<Self, DownloadError> {
+        let part_path = Self::part_path(path, suffix);
+        let file = File::create(&part_path).await?;
+
+        Ok(Self::from_file(path, part_path, file))
+    }
+
+    /// Opens the existing temporary file for the file to be saved at `path` so that data gets appended to it.
+    #[cfg(feature = "resource")]
+    pub(crate) async fn append(path: &Path, suffix: &str) -> Result<Self, DownloadError> {
+        let part_path = Self::part_path(path, suffix);
+        let file = OpenOptions::new().append(true).open(&part_path).await?;
+
+        Ok(Self::from_file(path, part_path, file))
+    }
+
+    fn from_file(path: &Path, part_path: PathBuf, file: File) -> Self {
+        Self {
+            path: path.to_owned(),
+            part_path,
+            file: Some(file),
+            keep: false,
+        }
+    }
+
+    /// Returns the temporary path the data is written to.
+    #[cfg(feature = "resource")]
+    pub(crate) fn temp_path(&self) -> &Path {
+        &self.part_path
+    }
+
+    /// Returns the temporary file the data must be written to.
+    pub(crate) fn file(&mut self) -> &mut File {
+        self.file
+            .as_mut()
+            .expect("the file is only taken when committing")
+    }
+
+    /// Keeps the temporary file on disk if the download does not complete, so that it can be resumed later on.
+    #[cfg(feature = "resource")]
+    pub(crate) fn keep(&mut self) {
+        self.keep = true;
+    }
+
+    /// Removes the temporary file, even if [`PartFile::keep`] was called.
+    #[cfg(feature = "resource")]
+    pub(crate) fn discard(mut self) {
+        self.keep = false;
+    }
+
+    /// Flushes and syncs the temporary file, then renames it to its final path which is returned.
+    pub(crate) async fn commit(mut self) -> Result<PathBuf, DownloadError> {
+        if let Some(mut file) = self.file.take() {
+            file.flush().await?;
+            file.sync_all().await?;
+        }
+
+        tokio::fs::rename(&self.part_path, &self.path).await?;
+
+        // the temporary file does not exist anymore
+        self.keep = true;
+
+        Ok(self.path.clone())
+    }
+}
+
+impl Drop for PartFile {
+    fn drop(&mut self) {
+        if self.keep {
+            return;
+        }
+
+        // the handle must be closed before the file can be removed on some platforms
+        drop(self.file.take());
+        let _ = std::fs::remove_file(&self.part_path);
+    }
+}
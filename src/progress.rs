@@ -0,0 +1,229 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+/// Minimum duration between two [`ProgressEvent::Progress`] events of the same item.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Event emitted while downloading, see [`Downloader::with_progress`](crate::Downloader::with_progress).
+///
+/// Every event carries the index of the item it relates to: `0` for a single file, the index of the video
+/// in a playlist or the index of the media in a tweet otherwise, so that concurrent downloads can be told apart.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressEvent {
+    /// The transfer of an item started. It is emitted again if the transfer is retried.
+    Started {
+        /// The index of the item.
+        item: usize,
+        /// The URL the item is downloaded from.
+        url: String,
+        /// The size of the item in bytes, if known.
+        total: Option<u64>,
+    },
+    /// Bytes of an item were received.
+    Progress {
+        /// The index of the item.
+        item: usize,
+        /// The number of bytes of the item downloaded so far.
+        downloaded: u64,
+        /// The size of the item in bytes, if known.
+        total: Option<u64>,
+        /// The average download speed since the transfer started, in bytes per second.
+        speed: f64,
+    },
+    /// An item was downloaded and saved.
+    Finished {
+        /// The index of the item.
+        item: usize,
+        /// The URL the item was downloaded from.
+        url: String,
+        /// The path the item was saved to.
+        path: PathBuf,
+        /// The size of the item in bytes.
+        downloaded: u64,
+    },
+    /// The download of an item failed.
+    Failed {
+        /// The index of the item.
+        item: usize,
+        /// The URL the item was downloaded from.
+        url: String,
+        /// The description of the error.
+        error: String,
+    },
+}
+
+/// Handler receiving the [`ProgressEvent`]s of a downloader.
+///
+/// It is called from the tasks performing the downloads, so it should return quickly.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::prelude::{Downloader, ProgressEvent, ProgressHandler, ResourceDownloader};
+///
+/// let mut downloader = ResourceDownloader::new("https://example.com/file.zip").unwrap();
+///
+/// downloader.with_progress(ProgressHandler::new(|event| {
+///     if let ProgressEvent::Progress { downloaded, total, .. } = event {
+///         println!("{} / {:?} bytes", downloaded, total);
+///     }
+/// }));
+/// ```
+#[derive(Clone)]
+pub struct ProgressHandler(Arc<dyn Fn(ProgressEvent) + Send + Sync>);
+
+impl ProgressHandler {
+    /// Creates a handler calling the given callback for each event.
+    pub fn new<F: Fn(ProgressEvent) + Send + Sync + 'static>(callback: F) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    /// Creates a handler sending the events to a channel, along with the receiving half of the channel.
+    ///
+    /// Events sent once the receiver is dropped are discarded.
+    pub fn channel() -> (Self, UnboundedReceiver<ProgressEvent>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let handler = Self::new(move |event| {
+            let _ = sender.send(event);
+        });
+
+        (handler, receiver)
+    }
+
+    /// Passes the event to the handler.
+    pub(crate) fn emit(&self, event: ProgressEvent) {
+        (self.0)(event)
+    }
+}
+
+impl fmt::Debug for ProgressHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressHandler").finish_non_exhaustive()
+    }
+}
+
+/// Emits the events of the transfer of an item, throttling [`ProgressEvent::Progress`] events.
+///
+/// It can be shared between the tasks downloading the segments of an item.
+#[derive(Debug)]
+pub(crate) struct ProgressTracker<'a> {
+    handler: Option<&'a ProgressHandler>,
+    item: usize,
+    total: Option<u64>,
+    /// Bytes downloaded before the transfer started, when resuming a download.
+    initial: u64,
+    received: AtomicU64,
+    started_at: Instant,
+    last_emitted_at: Mutex<Instant>,
+}
+
+impl<'a> ProgressTracker<'a> {
+    /// Starts tracking the transfer of an item from `url`, of which `initial` bytes were already downloaded,
+    /// and emits a [`ProgressEvent::Started`] event.
+    pub(crate) fn start(
+        handler: Option<&'a ProgressHandler>,
+        item: usize,
+        url: &str,
+        initial: u64,
+        total: Option<u64>,
+    ) -> Self {
+        if let Some(handler) = handler {
+            handler.emit(ProgressEvent::Started {
+                item,
+                url: url.to_owned(),
+                total,
+            });
+        }
+
+        let now = Instant::now();
+
+        Self {
+            handler,
+            item,
+            total,
+            initial,
+            received: AtomicU64::new(0),
+            started_at: now,
+            last_emitted_at: Mutex::new(now),
+        }
+    }
+
+    /// Records that `len` more bytes were received.
+    pub(crate) fn advance(&self, len: usize) {
+        let received = self.received.fetch_add(len as u64, Ordering::Relaxed) + len as u64;
+
+        let Some(handler) = self.handler else {
+            return;
+        };
+
+        let now = Instant::now();
+        let complete = self.total == Some(self.initial + received);
+
+        {
+            let mut last_emitted_at = self.last_emitted_at.lock().unwrap();
+
+            if !complete && now.duration_since(*last_emitted_at) < PROGRESS_INTERVAL {
+                return;
+            }
+
+            *last_emitted_at = now;
+        }
+
+        let elapsed = now.duration_since(self.started_at).as_secs_f64();
+
+        handler.emit(ProgressEvent::Progress {
+            item: self.item,
+            downloaded: self.initial + received,
+            total: self.total,
+            speed: if elapsed > 0.0 {
+                received as f64 / elapsed
+            } else {
+                0.0
+            },
+        });
+    }
+
+    /// Returns the number of bytes of the item downloaded so far.
+    pub(crate) fn downloaded(&self) -> u64 {
+        self.initial + self.received.load(Ordering::Relaxed)
+    }
+}
+
+/// Emits a [`ProgressEvent::Finished`] event if there is a handler.
+pub(crate) fn finished(
+    handler: Option<&ProgressHandler>,
+    item: usize,
+    url: &str,
+    path: PathBuf,
+    downloaded: u64,
+) {
+    if let Some(handler) = handler {
+        handler.emit(ProgressEvent::Finished {
+            item,
+            url: url.to_owned(),
+            path,
+            downloaded,
+        });
+    }
+}
+
+/// Emits a [`ProgressEvent::Failed`] event if there is a handler.
+pub(crate) fn failed(
+    handler: Option<&ProgressHandler>,
+    item: usize,
+    url: &str,
+    error: &dyn fmt::Display,
+) {
+    if let Some(handler) = handler {
+        handler.emit(ProgressEvent::Failed {
+            item,
+            url: url.to_owned(),
+            error: error.to_string(),
+        });
+    }
+}
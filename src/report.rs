@@ -0,0 +1,213 @@
+#[cfg(any(feature = "twitter", feature = "youtube"))]
+use crate::errors::DownloadError;
+#[cfg(feature = "resource")]
+use reqwest::header::{self, HeaderMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Summary of a download, listing the files that were written.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::prelude::{DownloadError, Downloader, YoutubeDownloader};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), DownloadError> {
+///     let downloader = YoutubeDownloader::new("https://www.youtube.com/playlist?list=playlist_id").unwrap();
+///     let report = downloader.download_to("./videos/").await?;
+///
+///     for file in report.files() {
+///         println!("{} ({} bytes) from {}", file.path.display(), file.size, file.url);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DownloadReport {
+    files: Vec<DownloadedFile>,
+}
+
+impl DownloadReport {
+    /// Creates an empty [`DownloadReport`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the files that were written, in the order of the items of the download.
+    pub fn files(&self) -> &[DownloadedFile] {
+        &self.files
+    }
+
+    /// Returns the paths of the files that were written.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|file| file.path.as_path())
+    }
+
+    /// Returns the total size of the files that were written, in bytes.
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+
+    /// Returns the number of files that were written.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns `true` if no file was written.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Merges the results of the downloads of several items into a single report.
+    ///
+    /// If any of them was cancelled, a [`DownloadError::Cancelled`] holding all the completed files is returned.
+    /// Otherwise, if any of them failed, the first error is returned, wrapped in a [`DownloadError::Partial`]
+    /// holding the completed files when some were saved. The errors are looked at without their [`DownloadError::Context`].
+    #[cfg(any(feature = "twitter", feature = "youtube"))]
+    pub(crate) fn from_results<I>(results: I) -> Result<Self, DownloadError>
+    where
+        I: IntoIterator<Item = Result<DownloadReport, DownloadError>>,
+    {
+        let mut report = Self::new();
+        let mut cancelled = false;
+        let mut error = None;
+
+        for result in results {
+            match result {
+                Ok(files) => report.extend(files),
+                Err(err) if matches!(err.inner(), DownloadError::Cancelled { .. }) => {
+                    cancelled = true;
+
+                    if let DownloadError::Cancelled { completed } = err.into_inner() {
+                        report.extend(completed);
+                    }
+                }
+                Err(err) if matches!(err.inner(), DownloadError::Partial { .. }) => {
+                    if let DownloadError::Partial {
+                        completed,
+                        error: err,
+                    } = err.into_inner()
+                    {
+                        report.extend(completed);
+                        error.get_or_insert(*err);
+                    }
+                }
+                Err(err) => {
+                    error.get_or_insert(err);
+                }
+            }
+        }
+
+        if cancelled {
+            return Err(DownloadError::Cancelled { completed: report });
+        }
+
+        match error {
+            Some(err) if report.is_empty() => Err(err),
+            Some(err) => Err(DownloadError::Partial {
+                completed: report,
+                error: Box::new(err),
+            }),
+            None => Ok(report),
+        }
+    }
+}
+
+impl From<DownloadedFile> for DownloadReport {
+    fn from(file: DownloadedFile) -> Self {
+        Self { files: vec![file] }
+    }
+}
+
+impl FromIterator<DownloadedFile> for DownloadReport {
+    fn from_iter<I: IntoIterator<Item = DownloadedFile>>(iter: I) -> Self {
+        Self {
+            files: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<DownloadedFile> for DownloadReport {
+    fn extend<I: IntoIterator<Item = DownloadedFile>>(&mut self, iter: I) {
+        self.files.extend(iter)
+    }
+}
+
+impl IntoIterator for DownloadReport {
+    type Item = DownloadedFile;
+    type IntoIter = std::vec::IntoIter<DownloadedFile>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.files.into_iter()
+    }
+}
+
+/// A file written by a download.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadedFile {
+    /// The final path of the file.
+    pub path: PathBuf,
+    /// The URL the file was downloaded from.
+    pub url: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The kind of media the file contains.
+    pub kind: FileKind,
+    /// The playback duration of the audio or video, when known.
+    pub media_duration: Option<Duration>,
+    /// The time spent downloading the file.
+    pub elapsed: Duration,
+    /// Whether the file already existed and was kept instead of being downloaded,
+    /// see [`OverwritePolicy`](crate::overwrite::OverwritePolicy).
+    pub skipped: bool,
+}
+
+impl DownloadedFile {
+    pub(crate) fn new(path: PathBuf, url: &str, size: u64, kind: FileKind) -> Self {
+        Self {
+            path,
+            url: url.to_owned(),
+            size,
+            kind,
+            media_duration: None,
+            elapsed: Duration::ZERO,
+            skipped: false,
+        }
+    }
+}
+
+/// Kind of media contained in a downloaded file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileKind {
+    /// A video, possibly with an audio track.
+    Video,
+    /// An audio track only.
+    Audio,
+    /// An image, including animated ones.
+    Image,
+    /// Any other kind of file.
+    Other,
+}
+
+impl FileKind {
+    /// Infers the kind of a file from the `Content-Type` header of the response it was downloaded from.
+    #[cfg(feature = "resource")]
+    pub(crate) fn from_content_type(headers: &HeaderMap) -> Self {
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        match content_type.split_once('/').map(|(kind, _)| kind) {
+            Some("video") => FileKind::Video,
+            Some("audio") => FileKind::Audio,
+            Some("image") => FileKind::Image,
+            _ => FileKind::Other,
+        }
+    }
+}
@@ -1,20 +1,77 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
+use crate::cancel::{self, CancellationToken};
+use crate::checksum::{Checksum, Hasher};
+use crate::errors::ErrorKind;
 use crate::header::HeaderMapBuilder;
+use crate::http::{HttpConfig, HttpTransport, Transport};
+use crate::info::{InfoJson, Media, MediaFormat, MediaInfo};
+use crate::overwrite::{Destination, OverwritePolicy};
+use crate::part_file::{PartFile, DEFAULT_PART_SUFFIX};
 use crate::prelude::{DownloadError, Downloader};
-use reqwest::{Client, Response};
-use tokio::fs::create_dir_all;
+use crate::progress::{self, ProgressHandler, ProgressTracker};
+use crate::report::{DownloadReport, DownloadedFile, FileKind};
+use crate::retry::{self, RetryPolicy};
+use crate::sanitize::FileNameSanitizer;
+use crate::template::{OutputTemplate, TemplateFields};
+use crate::trace::{self, Instrument};
+use futures::StreamExt;
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{Client, Response, StatusCode};
+use tokio::fs::{create_dir_all, OpenOptions};
+use tokio::io::AsyncSeekExt;
 use tokio::{fs::File, io::AsyncWriteExt};
 use url::Url;
 
+mod file_name;
+
+/// Name of the extractor of the resources, in errors and `.info.json` files.
+pub(crate) const EXTRACTOR: &str = "resource";
+
 #[derive(Debug, Clone)]
 /// The [`ResourceDownloader`] is designed for downloading resources directly from the internet, such as files hosted on a website.
 pub struct ResourceDownloader {
     url: Url,
     name: Option<String>,
-    print_download_status: bool,
+    /// Whether an existing partial file should be completed instead of downloaded again.
+    resume: bool,
+    /// Maximum number of byte ranges fetched in parallel.
+    segments: usize,
+    /// Minimum size in bytes of each of the byte ranges fetched in parallel.
+    min_segment_size: u64,
+    /// Suffix of the temporary file the resource is written to before being renamed.
+    part_suffix: String,
+    /// Expected digest of the resource.
+    checksum: Option<Checksum>,
+    retry_policy: RetryPolicy,
+    /// Policy applied when the file already exists.
+    overwrite_policy: OverwritePolicy,
+    /// Template of the path of the file, relative to the download folder.
+    output_template: Option<OutputTemplate>,
+    /// Sanitizer of the name of the file.
+    sanitizer: FileNameSanitizer,
+    /// Fields provided to the template on top of the ones of the resource, when it is part of a larger download.
+    template_fields: TemplateFields,
+    /// Client sending all the requests.
+    client: Client,
+    /// Configuration the client was built from.
+    http_config: HttpConfig,
+    /// Transport sending the requests instead of the client, if set.
+    transport: Option<Transport>,
+    /// Handler receiving the progress of the download.
+    progress: Option<ProgressHandler>,
+    /// Index of the resource in the progress events, when it is part of a larger download.
+    progress_item: usize,
+    /// Token cancelling the download.
+    cancellation: CancellationToken,
+    /// Whether a `.info.json` sidecar is written next to the file.
+    info_json: bool,
 }
 
+/// Default minimum size of a segment when downloading a resource over several connections, 1 MiB.
+pub const DEFAULT_MIN_SEGMENT_SIZE: u64 = 1024 * 1024;
+
 impl ResourceDownloader {
     /// Creates a new instance of [`ResourceDownloader`] with the provided URL.
     ///
@@ -38,81 +95,861 @@ impl ResourceDownloader {
         Ok(Self {
             url,
             name: None,
-            print_download_status: false,
+            resume: false,
+            segments: 1,
+            min_segment_size: DEFAULT_MIN_SEGMENT_SIZE,
+            part_suffix: DEFAULT_PART_SUFFIX.to_owned(),
+            checksum: None,
+            retry_policy: RetryPolicy::default(),
+            overwrite_policy: OverwritePolicy::default(),
+            output_template: None,
+            sanitizer: FileNameSanitizer::default(),
+            template_fields: TemplateFields::new(),
+            client: Client::new(),
+            http_config: HttpConfig::default(),
+            transport: None,
+            progress: None,
+            progress_item: 0,
+            cancellation: CancellationToken::new(),
+            info_json: false,
         })
     }
 
-    /// Sets the output filename for downloaded resources. If not set, the filename will be derived from the last part of the link path.
+    /// Sets the output filename for downloaded resources.
+    ///
+    /// If not set, the filename is taken from the `Content-Disposition` header of the response, or else derived from the last part of the link path.
+    /// When that name has no extension, one is inferred from the `Content-Type` of the response.
     ///
     /// **ONLY WORKS WITH `download` method, not `download_to`**
     pub fn with_name(&mut self, name: String) {
         self.name = Some(name);
     }
 
-    /// Retrieves the file name and replaces `\` and `/` by `|`
-    fn get_file_name(&self) -> String {
-        let name = match &self.name {
-            Some(name) => name,
-            None => self
-                .url
+    /// Enables resuming interrupted downloads.
+    ///
+    /// When the temporary file of an interrupted download (i.e. `<file>.part`) exists, only the missing bytes are requested
+    /// using an HTTP `Range` header and appended to it. The `ETag` (or `Last-Modified`) of the resource is kept in a `<file>.part.resume`
+    /// file next to the partial file until the download completes, so that the partial file is only completed if the resource
+    /// did not change in between.
+    ///
+    /// The name of the file is then taken from the response to a HEAD request, so that no body is requested before the range one.
+    /// Falls back to a full download if the server ignores the range request or if the resource changed.
+    pub fn resume(&mut self) -> &mut Self {
+        self.resume = true;
+        self
+    }
+
+    /// Enables segmented downloads, splitting the resource into at most `segments` byte ranges fetched in parallel
+    /// over separate connections and written at their offsets in the destination file.
+    ///
+    /// Segmented downloading only kicks in when the server advertises `Accept-Ranges: bytes` and the length of the resource,
+    /// otherwise the resource is downloaded over a single connection. The number of segments is reduced so that each one
+    /// is at least [`DEFAULT_MIN_SEGMENT_SIZE`] bytes long, see [`ResourceDownloader::with_min_segment_size`] to change it.
+    ///
+    /// The resource is also downloaded over a single connection when a checksum is set with [`ResourceDownloader::with_checksum`],
+    /// as its digest is computed from the data in the order it arrives.
+    pub fn segmented(&mut self, segments: usize) -> &mut Self {
+        self.segments = segments.max(1);
+        self
+    }
+
+    /// Sets the minimum size in bytes of each segment when segmented downloading is enabled.
+    pub fn with_min_segment_size(&mut self, size: u64) -> &mut Self {
+        self.min_segment_size = size.max(1);
+        self
+    }
+
+    /// Sets the expected digest of the resource.
+    ///
+    /// The digest is computed while the resource is being downloaded. If it does not match, the download fails with a
+    /// [`DownloadError::ChecksumMismatch`] and the downloaded data is deleted.
+    ///
+    /// Segmented downloading, see [`ResourceDownloader::segmented`], is disabled while a checksum is set, so that the
+    /// downloaded file does not have to be read again to compute its digest.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use rusty_dl::prelude::{Checksum, ResourceDownloader};
+    ///
+    /// let mut downloader = ResourceDownloader::new("https://example.com/release.tar.gz").unwrap();
+    /// downloader.with_checksum(Checksum::Sha256(
+    ///     "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855".to_owned(),
+    /// ));
+    /// ```
+    pub fn with_checksum(&mut self, checksum: Checksum) -> &mut Self {
+        self.checksum = Some(checksum);
+        self
+    }
+
+    /// Sets the index of the resource in the progress events, when it is downloaded as part of a larger download.
+    #[cfg(feature = "twitter")]
+    pub(crate) fn with_progress_item(&mut self, item: usize) -> &mut Self {
+        self.progress_item = item;
+        self
+    }
+
+    /// Sets fields provided to the output template on top of the ones of the resource, when it is downloaded as part of a larger download.
+    #[cfg(feature = "twitter")]
+    pub(crate) fn with_template_fields(&mut self, fields: TemplateFields) -> &mut Self {
+        self.template_fields = fields;
+        self
+    }
+
+    /// Returns the fields of the output template for the file named `name`.
+    fn fields_for(&self, name: &str) -> TemplateFields {
+        let name = Path::new(name);
+        let mut fields = TemplateFields::new();
+
+        fields
+            .set_opt("title", name.file_stem().map(|s| s.to_string_lossy()))
+            .set_opt("ext", name.extension().map(|s| s.to_string_lossy()))
+            .set("url", &self.url)
+            .set_opt("host", self.url.host_str())
+            .extend(&self.template_fields);
+
+        fields
+    }
+
+    /// Starts tracking the progress of a transfer of which `initial` bytes were already downloaded.
+    fn track_progress(&self, initial: u64, total: Option<u64>) -> ProgressTracker<'_> {
+        ProgressTracker::start(
+            self.progress.as_ref(),
+            self.progress_item,
+            self.url.as_str(),
+            initial,
+            total,
+        )
+    }
+
+    /// Retrieves the file name from the explicit name, the response headers or the URL and sanitizes it.
+    fn get_file_name(&self, headers: &HeaderMap) -> String {
+        if let Some(name) = &self.name {
+            return self.sanitizer.sanitize(name);
+        }
+
+        let name = file_name::from_content_disposition(headers).unwrap_or_else(|| {
+            self.url
                 .path_segments()
-                .and_then(|segments| segments.last())
-                .unwrap_or_else(|| self.url.as_str()),
+                .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+                .or_else(|| self.url.host_str())
+                .unwrap_or_else(|| self.url.as_str())
+                .to_owned()
+        });
+
+        let name = match file_name::extension_from_content_type(headers) {
+            Some(extension) if Path::new(&name).extension().is_none() => {
+                format!("{}.{}", name, extension)
+            }
+            _ => name,
         };
 
-        ResourceDownloader::sanitize_file_name(&name)
+        self.sanitizer.sanitize(&name)
+    }
+
+    /// Returns the format of the resource named `name` described by the response headers.
+    fn media_format(&self, headers: &HeaderMap, name: &str) -> MediaFormat {
+        let header_str = |name| headers.get(name).and_then(|v| v.to_str().ok());
+
+        MediaFormat {
+            format_id: None,
+            url: self.url.to_string(),
+            kind: FileKind::from_content_type(headers),
+            ext: Path::new(name)
+                .extension()
+                .map(|ext| ext.to_string_lossy().into_owned()),
+            mime_type: header_str(header::CONTENT_TYPE).map(|v| v.trim().to_owned()),
+            width: None,
+            height: None,
+            bitrate: None,
+            // the length of the body of a HEAD response is always 0, only the header is meaningful
+            size: header_str(header::CONTENT_LENGTH).and_then(|v| v.trim().parse().ok()),
+        }
+    }
+
+    /// Returns the sidecar of the resource named `name` described by the response headers.
+    fn info_json(&self, headers: &HeaderMap, name: &str) -> InfoJson {
+        InfoJson {
+            url: self.url.to_string(),
+            extractor: EXTRACTOR.to_owned(),
+            title: Path::new(name)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned()),
+            upload_date: last_modified_date(headers),
+            format: Some(self.media_format(headers, name)),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the transport sending the requests, the client of the downloader unless one was set.
+    fn transport(&self) -> &dyn HttpTransport {
+        match &self.transport {
+            Some(transport) => transport,
+            None => &self.client,
+        }
     }
 
     /// Sends a GET request to the URL of the resource and returns the response.
     async fn send_request(&self) -> Result<Response, DownloadError> {
-        let client = Client::new();
+        self.send_range_request(None, None).await
+    }
+
+    /// Sends a HEAD request to the URL of the resource, retrying it according to the retry policy.
+    async fn send_head_request(&self) -> Result<Response, DownloadError> {
+        let headers = HeaderMapBuilder::new().with_user_agent().build();
+
+        self.retry_policy
+            .send(self.transport(), || {
+                self.client.head(self.url.clone()).headers(headers.clone())
+            })
+            .await
+    }
+
+    /// Requests the headers telling the name of the resource and how to download it.
+    ///
+    /// Only a HEAD request is sent when the resource may be downloaded in segments or resumed, its body being then fetched
+    /// by range requests, unless the server does not answer it successfully. Otherwise a GET request is sent, and its response
+    /// is returned too so that its body gets downloaded.
+    async fn send_probe_request(&self) -> Result<(HeaderMap, Option<Response>), DownloadError> {
+        if self.may_segment() || self.resume {
+            let response = self.send_head_request().await?;
+
+            if response.status().is_success() {
+                return Ok((response.headers().clone(), None));
+            }
+        }
+
+        let response = DownloadError::check_status(self.send_request().await?).await?;
+
+        Ok((response.headers().clone(), Some(response)))
+    }
+
+    /// Sends a GET request to the URL of the resource, asking only for the given `(start, end)` byte range if provided.
+    /// The range is open-ended when `end` is `None`.
+    ///
+    /// When a `validator` is given, it is sent in an `If-Range` header so that the server returns the whole resource if it changed.
+    /// The request is sent again according to the retry policy if it fails with a transient error.
+    async fn send_range_request(
+        &self,
+        range: Option<(u64, Option<u64>)>,
+        validator: Option<&str>,
+    ) -> Result<Response, DownloadError> {
+        let mut headers_builder = HeaderMapBuilder::new().with_user_agent();
+
+        if let Some((start, end)) = range {
+            let end = end.map(|end| end.to_string()).unwrap_or_default();
+            let range = HeaderValue::from_str(&format!("bytes={}-{}", start, end))
+                .expect("Failed to create HeaderValue");
+            headers_builder = headers_builder.range(range);
 
-        let headers_builder = HeaderMapBuilder::new().with_user_agent();
+            if let Some(value) = validator.and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers_builder = headers_builder.if_range(value);
+            }
+        }
+
+        let headers = headers_builder.build();
+
+        self.retry_policy
+            .send(self.transport(), || {
+                self.client.get(self.url.clone()).headers(headers.clone())
+            })
+            .await
+    }
 
-        let response = client
-            .get(self.url.clone())
-            .headers(headers_builder.build())
-            .send()
+    /// Returns the path of the file storing the validator of the partially downloaded file at `part_path`.
+    fn resume_file_path(part_path: &Path) -> PathBuf {
+        let mut resume_path = part_path.as_os_str().to_owned();
+        resume_path.push(".resume");
+
+        PathBuf::from(resume_path)
+    }
+
+    /// Tries to continue the download of the partial file at `part_path` containing `downloaded` bytes.
+    ///
+    /// Returns the response to append to the file, the whole resource if the server sent it instead,
+    /// or [`Resume::Restart`] if the download must start over with a new request.
+    async fn try_resume(&self, part_path: &Path, downloaded: u64) -> Result<Resume, DownloadError> {
+        let validator = tokio::fs::read_to_string(Self::resume_file_path(part_path))
+            .await
+            .ok()
+            .map(|v| v.trim().to_owned())
+            .filter(|v| !v.is_empty());
+
+        let response = self
+            .send_range_request(Some((downloaded, None)), validator.as_deref())
             .await?;
-        Ok(response)
+
+        // the whole resource is sent when it changed or when the server does not support ranges
+        if response.status() == StatusCode::OK {
+            return Ok(Resume::Whole(response));
+        }
+
+        if response.status() != StatusCode::PARTIAL_CONTENT
+            || content_range_start(&response) != Some(downloaded)
+        {
+            return Ok(Resume::Restart);
+        }
+
+        // servers ignoring `If-Range` would send the new content from the wrong offset
+        if let (Some(expected), Some(current)) = (validator, response_validator(&response)) {
+            if expected != current {
+                return Ok(Resume::Restart);
+            }
+        }
+
+        Ok(Resume::Partial(response))
     }
-}
 
-#[async_trait::async_trait]
-impl Downloader for ResourceDownloader {
-    async fn download_to<P: AsRef<Path> + std::marker::Send>(
+    /// Returns `true` if the resource may be downloaded in segments, see [`ResourceDownloader::segmented`].
+    fn may_segment(&self) -> bool {
+        self.segments > 1 && self.checksum.is_none()
+    }
+
+    /// Returns the length of the resource and the number of segments to download it in,
+    /// if segmented downloading is enabled and supported by the server according to the response headers.
+    fn segments_for(&self, headers: &HeaderMap) -> Option<(u64, u64)> {
+        if !self.may_segment() {
+            return None;
+        }
+
+        let accepts_ranges = headers
+            .get(header::ACCEPT_RANGES)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.eq_ignore_ascii_case("bytes"));
+
+        if !accepts_ranges {
+            return None;
+        }
+
+        let length = content_length(headers)?;
+
+        let segments = (self.segments as u64).min(length / self.min_segment_size);
+
+        (segments > 1).then_some((length, segments))
+    }
+
+    /// Downloads the whole body of the response to the file at `path`, discarding any previous partial download.
+    ///
+    /// Returns the final path of the file and its size.
+    async fn download_whole(
         &self,
-        folder_path: P,
+        path: &Path,
+        response: Response,
+    ) -> Result<(PathBuf, u64), DownloadError> {
+        let mut part = PartFile::create(path, &self.part_suffix).await?;
+        let resume_path = Self::resume_file_path(part.temp_path());
+
+        if self.resume {
+            // a partial file is only worth keeping if it can be resumed later on
+            part.keep();
+
+            match response_validator(&response) {
+                Some(validator) => tokio::fs::write(&resume_path, validator).await?,
+                None => {
+                    let _ = tokio::fs::remove_file(&resume_path).await;
+                }
+            }
+        }
+
+        let mut hasher = self.checksum.as_ref().map(Checksum::hasher);
+        let tracker = self.track_progress(0, response.content_length());
+
+        write_body(
+            response,
+            part.file(),
+            hasher.as_mut(),
+            &tracker,
+            &self.cancellation,
+        )
+        .await?;
+        let path = self.finish(part, hasher).await?;
+
+        Ok((path, tracker.downloaded()))
+    }
+
+    /// Verifies the checksum of the downloaded data if any and moves the file to its final path which is returned,
+    /// or deletes it if the checksum does not match.
+    async fn finish(
+        &self,
+        part: PartFile,
+        hasher: Option<Hasher>,
+    ) -> Result<PathBuf, DownloadError> {
+        let resume_path = Self::resume_file_path(part.temp_path());
+
+        if let (Some(checksum), Some(hasher)) = (&self.checksum, hasher) {
+            if let Err(err) = checksum.verify(hasher, self.url.as_str()) {
+                part.discard();
+                let _ = tokio::fs::remove_file(&resume_path).await;
+
+                return Err(err);
+            }
+        }
+
+        let path = part.commit().await?;
+
+        if self.resume {
+            let _ = tokio::fs::remove_file(&resume_path).await;
+        }
+
+        Ok(path)
+    }
+
+    /// Downloads the resource of `length` bytes to `path` in `segments` byte ranges fetched in parallel.
+    ///
+    /// Returns the final path of the file and its size.
+    async fn download_segmented(
+        &self,
+        path: &Path,
+        length: u64,
+        segments: u64,
+    ) -> Result<(PathBuf, u64), DownloadError> {
+        let mut part = PartFile::create(path, &self.part_suffix).await?;
+        part.file().set_len(length).await?;
+
+        let segment_size = length.div_ceil(segments);
+        let tracker = self.track_progress(0, Some(length));
+
+        let tasks = (0..segments).map(|index| {
+            let start = index * segment_size;
+            let end = (start + segment_size).min(length) - 1;
+
+            self.download_segment(part.temp_path(), start, end, &tracker)
+        });
+
+        futures::future::try_join_all(tasks).await?;
+
+        let path = self.finish(part, None).await?;
+
+        Ok((path, length))
+    }
+
+    /// Downloads the bytes from `start` to `end` (inclusive) and writes them at the same offset in the file at `path`.
+    async fn download_segment(
+        &self,
+        path: &Path,
+        start: u64,
+        end: u64,
+        tracker: &ProgressTracker<'_>,
     ) -> Result<(), DownloadError> {
-        let name = self.get_file_name();
-        let path = folder_path
-            .as_ref()
-            .join(name);
+        let response = self
+            .send_range_request(Some((start, Some(end))), None)
+            .await?;
 
-        if self.print_download_status {
-            println!("Downloading...");
+        if response.status() != StatusCode::PARTIAL_CONTENT
+            || content_range_start(&response) != Some(start)
+        {
+            return Err(DownloadError::new(
+                ErrorKind::Unsupported,
+                format!(
+                    "The server did not honour the range `{}-{}` requested for `{}`.",
+                    start, end, self.url
+                ),
+            ));
         }
 
-        create_dir_all(folder_path).await?;
+        let mut file = OpenOptions::new().write(true).open(path).await?;
+        file.seek(std::io::SeekFrom::Start(start)).await?;
 
-        let mut file = File::create(path).await?;
+        let expected = end - start + 1;
+        let mut written = 0;
+        let mut stream = response.bytes_stream();
 
-        let response = self.send_request().await?;
-        let content = response.bytes().await?;
-        file.write_all(&content).await?;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            cancel::check(&self.cancellation)?;
+            written += chunk.len() as u64;
+
+            if written > expected {
+                return Err(DownloadError::new(
+                    ErrorKind::Network,
+                    format!(
+                        "Received more bytes than requested for the range `{}-{}` of `{}`.",
+                        start, end, self.url
+                    ),
+                ));
+            }
+
+            file.write_all(&chunk).await?;
+            tracker.advance(chunk.len());
+        }
+
+        if written != expected {
+            return Err(DownloadError::new(
+                ErrorKind::Network,
+                format!(
+                    "Received {} bytes instead of {} for the range `{}-{}` of `{}`.",
+                    written, expected, start, end, self.url
+                ),
+            ));
+        }
+
+        file.flush().await?;
 
         Ok(())
     }
 
-    async fn download(&self) -> Result<(), DownloadError> {
+    /// Downloads the resource to the folder at `folder_path` once, without retrying interrupted transfers.
+    async fn download_attempt(&self, folder_path: &Path) -> Result<DownloadedFile, DownloadError> {
+        // the headers are needed to know the name of the file
+        let (headers, response) = self.send_probe_request().await?;
+        let name = self.get_file_name(&headers);
+        let path = match &self.output_template {
+            Some(template) => {
+                let path =
+                    folder_path.join(template.render(&self.fields_for(&name), &self.sanitizer));
+
+                if let Some(parent) = path.parent() {
+                    create_dir_all(parent).await?;
+                }
+
+                path
+            }
+            None => folder_path.join(&name),
+        };
+        let kind = FileKind::from_content_type(&headers);
+
+        // the destination stays claimed until the file is written
+        let (path, _claim) = match self
+            .overwrite_policy
+            .resolve(&path, content_length(&headers))
+            .await?
+        {
+            Destination::Download(path, claim) => (path, claim),
+            Destination::Existing { path, size } => {
+                let mut file = DownloadedFile::new(path, self.url.as_str(), size, kind);
+                file.skipped = true;
+
+                return Ok(file);
+            }
+        };
+
+        let info_json = self.info_json.then(|| self.info_json(&headers, &name));
+
+        let (path, size) = self.transfer(&path, &headers, response).await?;
+        let file = DownloadedFile::new(path, self.url.as_str(), size, kind);
+
+        if let Some(info_json) = info_json {
+            info_json.write(&file).await?;
+        }
+
+        Ok(file)
+    }
+
+    /// Downloads the resource to `path`, resuming a previous download or splitting it into segments if enabled.
+    /// The `response` to the initial GET request, of which `headers` are given, is used if neither of them applies,
+    /// a new one being sent if only the headers were requested.
+    ///
+    /// Returns the final path of the file and its size.
+    async fn transfer(
+        &self,
+        path: &Path,
+        headers: &HeaderMap,
+        response: Option<Response>,
+    ) -> Result<(PathBuf, u64), DownloadError> {
+        if self.resume {
+            let part_path = PartFile::part_path(path, &self.part_suffix);
+            let downloaded = match tokio::fs::metadata(&part_path).await {
+                Ok(metadata) if metadata.is_file() => metadata.len(),
+                _ => 0,
+            };
+
+            if downloaded > 0 {
+                // only sent if the server did not answer the HEAD request
+                drop(response);
+
+                let response = match self.try_resume(&part_path, downloaded).await? {
+                    Resume::Partial(response) => response,
+                    Resume::Whole(response) => return self.download_whole(path, response).await,
+                    Resume::Restart => {
+                        let response =
+                            DownloadError::check_status(self.send_request().await?).await?;
+                        return self.download_whole(path, response).await;
+                    }
+                };
+
+                let mut part = PartFile::append(path, &self.part_suffix).await?;
+                part.keep();
+
+                // the bytes downloaded previously are part of the digest too
+                let mut hasher = self.checksum.as_ref().map(Checksum::hasher);
+                if let Some(hasher) = hasher.as_mut() {
+                    hasher.update_from_file(&part_path).await?;
+                }
+
+                let total = response.content_length().map(|len| len + downloaded);
+                let tracker = self.track_progress(downloaded, total);
+
+                write_body(
+                    response,
+                    part.file(),
+                    hasher.as_mut(),
+                    &tracker,
+                    &self.cancellation,
+                )
+                .await?;
+                let path = self.finish(part, hasher).await?;
+
+                return Ok((path, tracker.downloaded()));
+            }
+        }
+
+        if let Some((length, segments)) = self.segments_for(headers) {
+            drop(response);
+            return self.download_segmented(path, length, segments).await;
+        }
+
+        let response = match response {
+            Some(response) => response,
+            None => DownloadError::check_status(self.send_request().await?).await?,
+        };
+
+        self.download_whole(path, response).await
+    }
+
+    /// Downloads the resource to the folder at `folder_path`, reporting its completion or failure to the progress handler.
+    async fn download_to_folder(
+        &self,
+        folder_path: &Path,
+    ) -> Result<DownloadReport, DownloadError> {
+        trace::started();
+
+        create_dir_all(folder_path).await?;
+
+        let started_at = Instant::now();
+        let span = trace::item(self.progress_item, self.url.as_str());
+
+        // a transfer cut midway starts over, or resumes from the partial file if resuming is enabled
+        let attempts = self.retry_policy.run(retry::is_interrupted_transfer, || {
+            self.download_attempt(folder_path)
+        });
+        let result = cancel::or_cancelled(&self.cancellation, attempts)
+            .instrument(span.clone())
+            .await;
+
+        match result {
+            Ok(mut file) => {
+                file.elapsed = started_at.elapsed();
+                trace::finished(&span, &file);
+
+                progress::finished(
+                    self.progress.as_ref(),
+                    self.progress_item,
+                    self.url.as_str(),
+                    file.path.clone(),
+                    file.size,
+                );
+
+                Ok(file.into())
+            }
+            Err(err) => {
+                trace::failed(&span, &err);
+                progress::failed(
+                    self.progress.as_ref(),
+                    self.progress_item,
+                    self.url.as_str(),
+                    &err,
+                );
+
+                Err(err)
+            }
+        }
+    }
+
+    /// Retrieves the information of the resource, see [`Downloader::info`].
+    async fn fetch_info(&self) -> Result<MediaInfo, DownloadError> {
+        let response = cancel::or_cancelled(&self.cancellation, self.send_head_request()).await?;
+        let response = DownloadError::check_status(response).await?;
+        let headers = response.headers();
+
+        let name = self.get_file_name(headers);
+        let format = self.media_format(headers, &name);
+
+        Ok(MediaInfo {
+            url: self.url.to_string(),
+            title: Path::new(&name)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned()),
+            upload_date: last_modified_date(headers),
+            media: vec![Media {
+                kind: format.kind,
+                formats: vec![format],
+            }],
+            ..Default::default()
+        })
+    }
+}
+
+/// Extracts the validator of a response, that is its strong `ETag` or else its `Last-Modified` date.
+fn response_validator(response: &Response) -> Option<String> {
+    let headers = response.headers();
+
+    headers
+        .get(header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| {
+            headers
+                .get(header::LAST_MODIFIED)
+                .and_then(|v| v.to_str().ok())
+        })
+        .map(|v| v.to_owned())
+}
+
+/// Returns the `Last-Modified` date of a response as `YYYYMMDD`.
+fn last_modified_date(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(header::LAST_MODIFIED)?.to_str().ok()?;
+
+    compact_http_date(value)
+}
+
+/// Converts an HTTP date such as `Wed, 21 Oct 2015 07:28:00 GMT` to `YYYYMMDD`.
+fn compact_http_date(value: &str) -> Option<String> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    // formatting the parsed date converts the obsolete formats to the one above
+    let date = httpdate::fmt_http_date(httpdate::parse_http_date(value.trim()).ok()?);
+    let parts: Vec<&str> = date.split_whitespace().collect();
+    let [_, day, month, year, ..] = parts.as_slice() else {
+        return None;
+    };
+
+    let month = MONTHS.iter().position(|m| m == month)? + 1;
+
+    Some(format!("{}{:02}{}", year, month, day))
+}
+
+/// What the request continuing a partial download got, see [`ResourceDownloader::try_resume`].
+enum Resume {
+    /// The rest of the resource, to append to the partial file.
+    Partial(Response),
+    /// The whole resource, the server not resuming the download.
+    Whole(Response),
+    /// Nothing usable, the download must start over.
+    Restart,
+}
+
+/// Parses the `Content-Length` header, the length of the body of a HEAD response being always 0.
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Extracts the first byte position from the `Content-Range` header of a response, i.e. `N` in `bytes N-M/T`.
+fn content_range_start(response: &Response) -> Option<u64> {
+    let value = response
+        .headers()
+        .get(header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?;
+    let range = value.trim().strip_prefix("bytes")?.trim_start();
+    let (start, _) = range.split_once('-')?;
+
+    start.trim().parse().ok()
+}
+
+/// Writes the body of the response to the file chunk by chunk, feeding it to the hasher if any and reporting the progress.
+///
+/// Stops with a [`DownloadError::Cancelled`] before writing a chunk if the token was cancelled.
+async fn write_body(
+    response: Response,
+    file: &mut File,
+    mut hasher: Option<&mut Hasher>,
+    tracker: &ProgressTracker<'_>,
+    cancellation: &CancellationToken,
+) -> Result<(), DownloadError> {
+    // the body is written chunk by chunk as it arrives so that memory usage
+    // does not depend on the size of the resource
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        cancel::check(cancellation)?;
+
+        if let Some(hasher) = hasher.as_mut() {
+            hasher.update(&chunk);
+        }
+
+        file.write_all(&chunk).await?;
+        tracker.advance(chunk.len());
+    }
+
+    file.flush().await?;
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl Downloader for ResourceDownloader {
+    async fn download_to<P: AsRef<Path> + std::marker::Send>(
+        &self,
+        folder_path: P,
+    ) -> Result<DownloadReport, DownloadError> {
+        self.download_to_folder(folder_path.as_ref())
+            .instrument(trace::download(EXTRACTOR, self.url.as_str()))
+            .await
+            .map_err(|err| err.with_context(EXTRACTOR, self.url.as_str()))
+    }
+
+    async fn download(&self) -> Result<DownloadReport, DownloadError> {
         self.download_to(Path::new("./")).await
     }
 
+    /// Retrieves the information of the resource from the headers of a HEAD request.
+    async fn info(&self) -> Result<MediaInfo, DownloadError> {
+        self.fetch_info()
+            .await
+            .map_err(|err| err.with_context(EXTRACTOR, self.url.as_str()))
+    }
+
     fn is_valid_url(url: &Url) -> bool {
         url.has_host() && (url.scheme() == "https" || url.scheme() == "http")
     }
 
-    fn get_dl_status(&mut self) -> &mut bool {
-        &mut self.print_download_status
+    fn get_part_suffix(&mut self) -> &mut String {
+        &mut self.part_suffix
+    }
+
+    fn get_retry_policy(&mut self) -> &mut RetryPolicy {
+        &mut self.retry_policy
+    }
+
+    fn get_client(&mut self) -> &mut Client {
+        &mut self.client
+    }
+
+    fn get_http_config(&mut self) -> &mut HttpConfig {
+        &mut self.http_config
+    }
+
+    fn get_transport(&mut self) -> &mut Option<Transport> {
+        &mut self.transport
+    }
+
+    fn get_progress_handler(&mut self) -> &mut Option<ProgressHandler> {
+        &mut self.progress
+    }
+
+    fn get_output_template(&mut self) -> &mut Option<OutputTemplate> {
+        &mut self.output_template
+    }
+
+    fn get_file_name_sanitizer(&mut self) -> &mut FileNameSanitizer {
+        &mut self.sanitizer
+    }
+
+    fn get_overwrite_policy(&mut self) -> &mut OverwritePolicy {
+        &mut self.overwrite_policy
+    }
+
+    fn get_cancellation_token(&mut self) -> &mut CancellationToken {
+        &mut self.cancellation
+    }
+
+    fn get_info_json(&mut self) -> &mut bool {
+        &mut self.info_json
     }
 }
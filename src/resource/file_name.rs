@@ -0,0 +1,165 @@
+use percent_encoding::percent_decode_str;
+use reqwest::header::{self, HeaderMap};
+
+/// Extracts the file name advertised by the `Content-Disposition` header, as defined in RFC 6266.
+///
+/// The extended `filename*` parameter (RFC 5987 encoding) takes precedence over the `filename` one.
+/// Any directory component is stripped from the returned name.
+pub fn from_content_disposition(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(header::CONTENT_DISPOSITION)?;
+    let value = String::from_utf8_lossy(value.as_bytes());
+
+    let mut filename = None;
+    let mut extended_filename = None;
+
+    // the first part is the disposition type, i.e. `attachment` or `inline`
+    for param in split_params(&value).into_iter().skip(1) {
+        let Some((key, value)) = param.split_once('=') else {
+            continue;
+        };
+
+        match key.trim().to_ascii_lowercase().as_str() {
+            "filename*" => extended_filename = decode_extended_value(value.trim()),
+            "filename" => filename = Some(unquote(value.trim())),
+            _ => {}
+        }
+    }
+
+    extended_filename
+        .or(filename)
+        .map(|name| strip_directories(&name).to_owned())
+        .filter(|name| !name.is_empty() && name != "." && name != "..")
+}
+
+/// Returns the usual file extension of the media type advertised by the `Content-Type` header.
+pub fn extension_from_content_type(headers: &HeaderMap) -> Option<&'static str> {
+    let value = headers.get(header::CONTENT_TYPE)?.to_str().ok()?;
+    let mime = value.split(';').next()?.trim().to_ascii_lowercase();
+
+    let extension = match mime.as_str() {
+        "text/plain" => "txt",
+        "text/html" => "html",
+        "text/css" => "css",
+        "text/csv" => "csv",
+        "text/javascript" | "application/javascript" => "js",
+        "text/markdown" => "md",
+        "text/xml" | "application/xml" => "xml",
+        "application/json" => "json",
+        "application/pdf" => "pdf",
+        "application/zip" => "zip",
+        "application/gzip" | "application/x-gzip" => "gz",
+        "application/x-tar" => "tar",
+        "application/x-bzip2" => "bz2",
+        "application/x-xz" => "xz",
+        "application/x-7z-compressed" => "7z",
+        "application/vnd.rar" | "application/x-rar-compressed" => "rar",
+        "application/wasm" => "wasm",
+        "application/msword" => "doc",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "docx",
+        "application/vnd.ms-excel" => "xls",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xlsx",
+        "application/vnd.ms-powerpoint" => "ppt",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation" => "pptx",
+        "application/epub+zip" => "epub",
+        "application/manifest+json" => "webmanifest",
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "image/bmp" => "bmp",
+        "image/tiff" => "tiff",
+        "image/avif" => "avif",
+        "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+        "audio/mpeg" => "mp3",
+        "audio/ogg" => "ogg",
+        "audio/wav" | "audio/x-wav" => "wav",
+        "audio/webm" => "weba",
+        "audio/aac" => "aac",
+        "audio/flac" => "flac",
+        "audio/mp4" => "m4a",
+        "video/mp4" => "mp4",
+        "video/webm" => "webm",
+        "video/ogg" => "ogv",
+        "video/quicktime" => "mov",
+        "video/x-msvideo" => "avi",
+        "video/x-matroska" => "mkv",
+        "video/mp2t" => "ts",
+        "application/vnd.apple.mpegurl" | "application/x-mpegurl" => "m3u8",
+        "font/woff" => "woff",
+        "font/woff2" => "woff2",
+        "font/ttf" => "ttf",
+        "font/otf" => "otf",
+        _ => return None,
+    };
+
+    Some(extension)
+}
+
+/// Splits header parameters on `;`, ignoring the ones inside quoted strings.
+fn split_params(value: &str) -> Vec<&str> {
+    let mut params = Vec::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                params.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    params.push(&value[start..]);
+    params
+}
+
+/// Removes the surrounding quotes and backslash escapes of a quoted string.
+fn unquote(value: &str) -> String {
+    let Some(inner) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    else {
+        return value.to_owned();
+    };
+
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.extend(chars.next()),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Decodes an RFC 5987 extended value, i.e. `UTF-8'en'na%C3%AFve.txt`.
+fn decode_extended_value(value: &str) -> Option<String> {
+    let mut parts = value.splitn(3, '\'');
+    let charset = parts.next()?.to_ascii_lowercase();
+    let _language = parts.next()?;
+    let encoded = parts.next()?;
+
+    let bytes: Vec<u8> = percent_decode_str(encoded).collect();
+
+    match charset.as_str() {
+        "utf-8" => String::from_utf8(bytes).ok(),
+        // each ISO-8859-1 byte maps to the unicode code point of the same value
+        "iso-8859-1" => Some(bytes.into_iter().map(char::from).collect()),
+        _ => None,
+    }
+}
+
+/// Only keeps the last component of a path, as names must not point outside of the destination folder.
+fn strip_directories(name: &str) -> &str {
+    name.rsplit(['/', '\\']).next().unwrap_or(name).trim()
+}
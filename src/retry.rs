@@ -0,0 +1,277 @@
+use crate::errors::{DownloadError, ErrorKind};
+use crate::http::HttpTransport;
+use crate::trace::{self, Instrument};
+use reqwest::{header, RequestBuilder, Response, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime};
+
+/// Policy deciding how transient failures (connection resets, timeouts, `429` and `5xx` responses) are retried.
+///
+/// Retries are spaced by an exponential backoff: the n-th retry waits `base_delay * 2^(n-1)`, capped to `max_delay`.
+/// With jitter enabled, a random part of that delay is skipped so that concurrent downloads do not retry in lockstep.
+///
+/// ## Examples
+///
+/// ```
+/// use rusty_dl::retry::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::new()
+///     .max_attempts(5)
+///     .base_delay(Duration::from_secs(1))
+///     .retry_on_status(403);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    retryable_statuses: Vec<StatusCode>,
+    respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retryable_statuses: vec![
+                StatusCode::REQUEST_TIMEOUT,
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates the default [`RetryPolicy`]: 3 attempts, starting with a 500ms delay, retrying on `408`, `429`, `500`, `502`, `503` and `504`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a [`RetryPolicy`] that never retries.
+    pub fn never() -> Self {
+        Self::default().max_attempts(1)
+    }
+
+    /// Sets the maximum number of attempts, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry, doubled on each following retry.
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Sets the maximum delay between two attempts, also capping the delays requested by `Retry-After` headers.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Enables or disables the randomisation of the delays.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the status codes of the responses that are retried, replacing the default ones.
+    pub fn retryable_statuses<I: IntoIterator<Item = StatusCode>>(mut self, statuses: I) -> Self {
+        self.retryable_statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Adds a status code to the ones that are retried. Invalid status codes are ignored.
+    pub fn retry_on_status(mut self, status: u16) -> Self {
+        if let Ok(status) = StatusCode::from_u16(status) {
+            if !self.retryable_statuses.contains(&status) {
+                self.retryable_statuses.push(status);
+            }
+        }
+
+        self
+    }
+
+    /// Sets whether the delay requested by the `Retry-After` header of a response is waited instead of the computed one.
+    pub fn respect_retry_after(mut self, respect: bool) -> Self {
+        self.respect_retry_after = respect;
+        self
+    }
+
+    /// Returns the maximum number of attempts, including the first one.
+    pub fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Returns `true` if responses with the given status are retried.
+    pub fn is_retryable_status(&self, status: StatusCode) -> bool {
+        self.retryable_statuses.contains(&status)
+    }
+
+    /// Returns the delay to wait after the given failed attempt (starting at 1).
+    fn delay_for(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(delay) = retry_after.filter(|_| self.respect_retry_after) {
+            return delay.min(self.max_delay);
+        }
+
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+
+        if !self.jitter {
+            return delay;
+        }
+
+        // waits between half and the whole of the delay, the hasher being randomly seeded
+        let random = RandomState::new().build_hasher().finish();
+        let ratio = 0.5 + (random % 1000) as f64 / 2000.0;
+
+        delay.mul_f64(ratio)
+    }
+
+    /// Sends the request built by `build` through the transport, sending it again while it fails with a transient error
+    /// or a response with a retryable status, and attempts remain.
+    ///
+    /// The last response is returned as is once the attempts are exhausted, its status must still be checked.
+    #[cfg(any(feature = "resource", feature = "youtube"))]
+    pub(crate) async fn send<F>(
+        &self,
+        transport: &dyn HttpTransport,
+        build: F,
+    ) -> Result<Response, DownloadError>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 1;
+
+        loop {
+            let request = build().build()?;
+            let span = trace::request(request.method(), request.url(), attempt);
+
+            let result = transport.execute(request).instrument(span.clone()).await;
+            if let Ok(response) = &result {
+                trace::response(&span, response.status());
+            }
+
+            let retry_after = match result {
+                Ok(response)
+                    if attempt < self.max_attempts
+                        && self.is_retryable_status(response.status()) =>
+                {
+                    retry_after(&response)
+                }
+                Ok(response) => return Ok(response),
+                Err(err) if attempt < self.max_attempts && is_transient_request(&err) => None,
+                Err(err) => return Err(err),
+            };
+
+            let delay = self.delay_for(attempt, retry_after);
+            trace::retrying(attempt, delay);
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Runs the operation created by `operation`, running it again while it fails with an error
+    /// for which `retryable` returns `true` and attempts remain.
+    pub(crate) async fn run<T, F, Fut, R>(
+        &self,
+        retryable: R,
+        operation: F,
+    ) -> Result<T, DownloadError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, DownloadError>>,
+        R: Fn(&DownloadError) -> bool,
+    {
+        let mut attempt = 1;
+
+        loop {
+            match operation().await {
+                Err(err) if attempt < self.max_attempts && retryable(&err) => {
+                    let delay = self.delay_for(attempt, None);
+                    trace::retrying(attempt, delay);
+
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Returns `true` if the request failed before a response was received for a reason that may not happen again,
+/// such as a timeout or a connection error.
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.is_connect() || err.is_request()
+}
+
+/// Returns `true` if the request failed before a response was received for a reason that may not happen again:
+/// a transient [`reqwest::Error`], or a network error of a custom transport.
+fn is_transient_request(err: &DownloadError) -> bool {
+    match reqwest_source(err) {
+        Some(err) => is_transient(err),
+        None => err.kind() == ErrorKind::Network,
+    }
+}
+
+/// Returns `true` if the error interrupted the transfer of a response body.
+///
+/// Errors occurring before a response is received are not considered, as they are already retried by [`RetryPolicy::send`].
+#[cfg(feature = "resource")]
+pub(crate) fn is_interrupted_transfer(err: &DownloadError) -> bool {
+    reqwest_source(err).is_some_and(|err| err.is_body() || err.is_decode())
+}
+
+/// Returns `true` if one of the sources of the error is a transient [`reqwest::Error`], whether it happened
+/// before a response was received or during the transfer of its body.
+#[cfg(feature = "youtube")]
+pub(crate) fn is_transient_error(err: &DownloadError) -> bool {
+    reqwest_source(err).is_some_and(|err| is_transient(err) || err.is_body() || err.is_decode())
+}
+
+/// Walks the sources of the error to find the [`reqwest::Error`] that caused it, if any.
+fn reqwest_source(err: &DownloadError) -> Option<&reqwest::Error> {
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(err);
+
+    while let Some(err) = source {
+        if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+            return Some(err);
+        }
+
+        source = err.source();
+    }
+
+    None
+}
+
+/// Parses the `Retry-After` header of a response, either a number of seconds or an HTTP date.
+#[cfg(any(feature = "resource", feature = "youtube"))]
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(header::RETRY_AFTER)?.to_str().ok()?;
+
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value.trim()).ok()?;
+
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
@@ -0,0 +1,233 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// Default maximum length of a file name in bytes, the limit of most file systems.
+pub const DEFAULT_MAX_FILE_NAME_LENGTH: usize = 255;
+
+/// Maximum length in bytes of an extension preserved when truncating a file name.
+const MAX_EXTENSION_LENGTH: usize = 16;
+
+/// Characters that cannot appear in file names on Windows.
+const WINDOWS_RESERVED_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Names of devices that cannot be used as file names on Windows, with or without extension.
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Punctuation kept in [`SanitizeMode::StrictAscii`] file names, on top of ASCII letters and digits.
+const ASCII_PUNCTUATION: &str = " !#$%&'()+,-.;=@[]^_`{}~";
+
+/// Set of rules a file name must follow.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SanitizeMode {
+    /// Only ASCII letters, digits, spaces and common punctuation, other characters being transliterated to ASCII
+    /// when possible (`é` becomes `e`). Names also follow the rules of [`SanitizeMode::Windows`].
+    StrictAscii,
+    /// Names valid on Windows, and thus on the other platforms too: the characters `<>:"/\|?*` are replaced,
+    /// trailing dots and spaces removed and device names such as `CON` or `LPT1` altered. This is the default.
+    #[default]
+    Windows,
+    /// Names only valid on POSIX file systems, where solely `/` and control characters are replaced.
+    Posix,
+}
+
+/// Unicode normalization form applied to file names, so that visually identical names are identical on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Normalization {
+    /// Canonical composition, e.g. `e` followed by a combining accent becomes `é`.
+    Nfc,
+    /// Canonical decomposition, the form used by macOS file systems.
+    Nfd,
+    /// Compatibility composition, which also replaces characters such as ligatures or full-width letters.
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+/// Turns arbitrary strings, such as video titles, into valid file names.
+///
+/// By default names are made valid on Windows, normalized to [`Normalization::Nfc`], invalid characters are replaced
+/// by `_` and names are truncated to [`DEFAULT_MAX_FILE_NAME_LENGTH`] bytes, keeping their extension.
+///
+/// ## Examples
+///
+/// ```
+/// use rusty_dl::sanitize::{FileNameSanitizer, SanitizeMode};
+///
+/// let sanitizer = FileNameSanitizer::new();
+/// assert_eq!(sanitizer.sanitize("AC/DC: Live?.mp4"), "AC_DC_ Live_.mp4");
+///
+/// let sanitizer = FileNameSanitizer::new()
+///     .mode(SanitizeMode::StrictAscii)
+///     .replacement(None)
+///     .max_length(16);
+/// assert_eq!(sanitizer.sanitize("Café crème au lait.mp3"), "Cafe creme a.mp3");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileNameSanitizer {
+    mode: SanitizeMode,
+    normalization: Option<Normalization>,
+    replacement: Option<char>,
+    max_length: usize,
+}
+
+impl Default for FileNameSanitizer {
+    fn default() -> Self {
+        Self {
+            mode: SanitizeMode::default(),
+            normalization: Some(Normalization::Nfc),
+            replacement: Some('_'),
+            max_length: DEFAULT_MAX_FILE_NAME_LENGTH,
+        }
+    }
+}
+
+impl FileNameSanitizer {
+    /// Creates the default [`FileNameSanitizer`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the rules the file names must follow.
+    pub fn mode(mut self, mode: SanitizeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the Unicode normalization form applied to file names, or disables normalization with `None`.
+    pub fn normalization(mut self, normalization: Option<Normalization>) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Sets the character replacing invalid characters, or removes them with `None`.
+    ///
+    /// A replacement which is itself invalid in the current mode is ignored and invalid characters are removed.
+    pub fn replacement(mut self, replacement: Option<char>) -> Self {
+        self.replacement = replacement;
+        self
+    }
+
+    /// Sets the maximum length of file names in bytes. Longer names are truncated on a character boundary, keeping their extension.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length.max(1);
+        self
+    }
+
+    /// Returns the file name corresponding to `name` according to the rules of the sanitizer.
+    ///
+    /// The result is never empty, nor `.` or `..`, so that it always designates a file within its folder.
+    pub fn sanitize(&self, name: &str) -> String {
+        let name: String = match self.normalization {
+            Some(Normalization::Nfc) => name.nfc().collect(),
+            Some(Normalization::Nfd) => name.nfd().collect(),
+            Some(Normalization::Nfkc) => name.nfkc().collect(),
+            Some(Normalization::Nfkd) => name.nfkd().collect(),
+            None => name.to_owned(),
+        };
+
+        let name = match self.mode {
+            SanitizeMode::StrictAscii => deunicode::deunicode_with_tofu(&name, "\u{FFFD}"),
+            _ => name,
+        };
+
+        let replacement = self.replacement.filter(|c| self.is_valid_char(*c));
+        let mut sanitized = String::with_capacity(name.len());
+
+        for c in name.chars() {
+            if self.is_valid_char(c) {
+                sanitized.push(c);
+            } else if let Some(replacement) = replacement {
+                sanitized.push(replacement);
+            }
+        }
+
+        let mut sanitized = self.trim(&sanitized).to_owned();
+
+        if self.mode != SanitizeMode::Posix && is_windows_reserved_name(&sanitized) {
+            // `CON.txt` becomes `CON_.txt`
+            let stem_end = sanitized.find('.').unwrap_or(sanitized.len());
+            sanitized.insert(stem_end, replacement.unwrap_or('_'));
+        }
+
+        let sanitized = self.truncate(&sanitized);
+
+        match sanitized.as_str() {
+            "" | "." | ".." => "_".to_owned(),
+            _ => sanitized,
+        }
+    }
+
+    /// Returns `true` if the character may appear in a file name.
+    fn is_valid_char(&self, c: char) -> bool {
+        if c.is_control() {
+            return false;
+        }
+
+        match self.mode {
+            SanitizeMode::StrictAscii => c.is_ascii_alphanumeric() || ASCII_PUNCTUATION.contains(c),
+            SanitizeMode::Windows => !WINDOWS_RESERVED_CHARS.contains(&c),
+            SanitizeMode::Posix => c != '/',
+        }
+    }
+
+    /// Removes the leading and trailing whitespaces, as well as the trailing dots unless in [`SanitizeMode::Posix`].
+    fn trim<'a>(&self, name: &'a str) -> &'a str {
+        match self.mode {
+            SanitizeMode::Posix => name.trim(),
+            _ => name
+                .trim_start()
+                .trim_end_matches(|c: char| c == '.' || c.is_whitespace()),
+        }
+    }
+
+    /// Truncates the name to the maximum length, keeping its extension if it is short enough.
+    fn truncate(&self, name: &str) -> String {
+        if name.len() <= self.max_length {
+            return name.to_owned();
+        }
+
+        let (stem, extension) = match name.rsplit_once('.') {
+            Some((stem, extension))
+                if !stem.is_empty()
+                    && extension.len() <= MAX_EXTENSION_LENGTH
+                    && extension.len() + 1 < self.max_length =>
+            {
+                (stem, Some(extension))
+            }
+            _ => (name, None),
+        };
+
+        let stem_length = self.max_length - extension.map_or(0, |ext| ext.len() + 1);
+        let stem = match self.trim(truncate_on_char_boundary(stem, stem_length)) {
+            "" => "_",
+            stem => stem,
+        };
+
+        match extension {
+            Some(extension) => format!("{}.{}", stem, extension),
+            None => stem.to_owned(),
+        }
+    }
+}
+
+/// Returns the longest prefix of `s` of at most `max_length` bytes ending on a character boundary.
+fn truncate_on_char_boundary(s: &str, max_length: usize) -> &str {
+    let mut end = max_length.min(s.len());
+
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    &s[..end]
+}
+
+/// Returns `true` if the name, ignoring its extensions, designates a device on Windows.
+fn is_windows_reserved_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+
+    WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+}
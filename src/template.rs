@@ -0,0 +1,246 @@
+use crate::errors::DownloadError;
+use crate::sanitize::FileNameSanitizer;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+/// Value rendered in place of the fields a downloader does not know.
+const MISSING_FIELD: &str = "NA";
+
+/// Template of the paths downloads are saved to, relative to the folder passed to
+/// [`Downloader::download_to`](crate::Downloader::download_to).
+///
+/// Fields are written between braces and replaced by the metadata of each downloaded file, e.g.
+/// `{uploader}/{upload_date}-{title}-{id}.{ext}`. A field can be padded to a minimum width with `{field:N}`,
+/// or with zeros with `{field:0N}` as in `{index:03}`. Braces are escaped by doubling them, `{{` and `}}`.
+/// Slashes in the template create subdirectories, while slashes in field values are replaced.
+/// Fields unknown to a downloader are rendered as `NA`. Each component of the rendered path is then sanitized
+/// by the [`FileNameSanitizer`] of the downloader.
+///
+/// The fields available depend on the downloader:
+/// - all of them: `title`, `ext` and `url`
+/// - [`ResourceDownloader`](crate::resource::ResourceDownloader): `host`
+/// - [`YoutubeDownloader`](crate::youtube::YoutubeDownloader): `id`, `uploader`, `channel_id`, `upload_date` (`YYYYMMDD`),
+///   `duration` (in seconds), and `playlist`, `playlist_id` and `index` (starting from 1) for the videos of a playlist
+/// - [`TwitterDownloader`](crate::twitter::TwitterDownloader): `id` (of the tweet), `uploader` (the screen name of the author),
+///   `uploader_name`, `upload_date` (`YYYYMMDD`) and `index` (of the media in the tweet, starting from 1).
+///   The `title` is the text of the tweet.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::prelude::{Downloader, OutputTemplate, YoutubeDownloader};
+///
+/// let template = OutputTemplate::parse("{playlist}/{index:03} - {title}.{ext}").unwrap();
+///
+/// let mut downloader = YoutubeDownloader::new("https://www.youtube.com/playlist?list=playlist_id").unwrap();
+/// downloader.with_output_template(template);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTemplate {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Field {
+        name: String,
+        width: usize,
+        zero_padded: bool,
+    },
+}
+
+impl OutputTemplate {
+    /// Parses an output template.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`DownloadError::InvalidTemplate`] if a brace is not closed, a field name or width is invalid,
+    /// or if the template is absolute or contains `.` or `..` components.
+    pub fn parse(template: &str) -> Result<Self, DownloadError> {
+        let invalid = |reason: &str| {
+            DownloadError::InvalidTemplate(format!(
+                "Invalid output template `{}`: {}.",
+                template, reason
+            ))
+        };
+
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(invalid("unmatched `}`, use `}}` to write a brace")),
+                '{' => {
+                    let mut field = String::new();
+
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => field.push(c),
+                            None => return Err(invalid("unclosed `{`")),
+                        }
+                    }
+
+                    let (name, spec) = field.split_once(':').unwrap_or((&field, ""));
+
+                    if name.is_empty()
+                        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                    {
+                        return Err(invalid(&format!("invalid field name `{}`", name)));
+                    }
+
+                    let zero_padded = spec.starts_with('0');
+                    let width = match spec {
+                        "" => 0,
+                        spec => spec
+                            .parse()
+                            .map_err(|_| invalid(&format!("invalid width `{}`", spec)))?,
+                    };
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+
+                    segments.push(Segment::Field {
+                        name: name.to_owned(),
+                        width,
+                        zero_padded,
+                    });
+                }
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        if segments.is_empty() {
+            return Err(invalid("the template is empty"));
+        }
+
+        let path = Path::new(template);
+        if path.has_root()
+            || path
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            return Err(invalid(
+                "the template must be a relative path without `.` or `..` components",
+            ));
+        }
+
+        Ok(Self {
+            source: template.to_owned(),
+            segments,
+        })
+    }
+
+    /// Returns the template as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Renders the path of a file from its fields, each component of the path being sanitized.
+    pub(crate) fn render(&self, fields: &TemplateFields, sanitizer: &FileNameSanitizer) -> PathBuf {
+        let mut rendered = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => rendered.push_str(literal),
+                Segment::Field {
+                    name,
+                    width,
+                    zero_padded,
+                } => match fields.get(name) {
+                    Some(value) => {
+                        let value = sanitize_field(value);
+                        let padding = width.saturating_sub(value.chars().count());
+                        let pad = if *zero_padded { '0' } else { ' ' };
+
+                        rendered.extend(std::iter::repeat_n(pad, padding));
+                        rendered.push_str(&value);
+                    }
+                    None => rendered.push_str(MISSING_FIELD),
+                },
+            }
+        }
+
+        // the sanitizer also prevents fields rendered as `.` or `..` from escaping the download folder
+        rendered
+            .split(['/', '\\'])
+            .filter(|component| !component.is_empty())
+            .map(|component| sanitizer.sanitize(component))
+            .collect()
+    }
+}
+
+impl FromStr for OutputTemplate {
+    type Err = DownloadError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for OutputTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Metadata of a downloaded file, available to output templates by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct TemplateFields(BTreeMap<String, String>);
+
+impl TemplateFields {
+    /// Creates an empty set of fields.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of a field, replacing any previous one.
+    pub(crate) fn set(&mut self, name: &str, value: impl ToString) -> &mut Self {
+        self.0.insert(name.to_owned(), value.to_string());
+        self
+    }
+
+    /// Sets the value of a field if there is one.
+    pub(crate) fn set_opt(&mut self, name: &str, value: Option<impl ToString>) -> &mut Self {
+        if let Some(value) = value {
+            self.set(name, value);
+        }
+
+        self
+    }
+
+    /// Returns the value of a field.
+    pub(crate) fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    /// Sets all the fields of `other`, replacing the ones already set.
+    pub(crate) fn extend(&mut self, other: &TemplateFields) -> &mut Self {
+        self.0
+            .extend(other.0.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self
+    }
+}
+
+/// Replaces the characters of a field value which would create subdirectories.
+fn sanitize_field(value: &str) -> String {
+    value.replace(['\\', '/'], "_")
+}
@@ -0,0 +1,143 @@
+//! Instrumentation of the downloads and of the requests with [`tracing`](https://docs.rs/tracing) spans and events.
+//!
+//! The spans and events are only emitted with the `tracing` feature, the functions of this module being no-ops without it.
+//! Nothing is printed by the crate itself: an application installs a subscriber to display or record them.
+//!
+//! - a `download` span covers each call to [`Downloader::download_to`](crate::Downloader::download_to),
+//!   with the `extractor` and the `url` of the downloader.
+//! - an `item` span covers the transfer of each file, with the `item` index and the `url` of the file.
+//!   Its `bytes` and `elapsed_ms` are recorded once the file is written.
+//! - a `request` span, at the `DEBUG` level, covers each HTTP request, with its `method`, `url` and `attempt`.
+//!   Its `status` is recorded once the response is received.
+
+use crate::errors::DownloadError;
+use crate::report::DownloadedFile;
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+use url::Url;
+
+#[cfg(feature = "tracing")]
+pub(crate) use tracing::{Instrument, Span};
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) use self::noop::{Instrument, Span};
+
+/// Returns the span of a download with the downloader of `extractor`.
+#[cfg(feature = "tracing")]
+pub(crate) fn download(extractor: &'static str, url: &str) -> Span {
+    tracing::info_span!("download", extractor, url)
+}
+
+/// Returns the span of the transfer of the file at `url`, the `item`-th of a download.
+#[cfg(feature = "tracing")]
+pub(crate) fn item(item: usize, url: &str) -> Span {
+    tracing::info_span!(
+        "item",
+        item,
+        url,
+        bytes = tracing::field::Empty,
+        elapsed_ms = tracing::field::Empty
+    )
+}
+
+/// Returns the span of an HTTP request, `attempt` starting from 1.
+#[cfg(feature = "tracing")]
+pub(crate) fn request(method: &Method, url: &Url, attempt: u32) -> Span {
+    tracing::debug_span!(
+        "request",
+        method = %method,
+        url = %url,
+        attempt,
+        status = tracing::field::Empty
+    )
+}
+
+/// Emits the event of the start of a download in the current span.
+#[cfg(feature = "tracing")]
+pub(crate) fn started() {
+    tracing::info!("downloading");
+}
+
+/// Records the size and the transfer time of the written file in its item `span`, and emits the event of its completion.
+#[cfg(feature = "tracing")]
+pub(crate) fn finished(span: &Span, file: &DownloadedFile) {
+    let elapsed_ms = file.elapsed.as_millis() as u64;
+    span.record("bytes", file.size);
+    span.record("elapsed_ms", elapsed_ms);
+
+    tracing::info!(
+        parent: span,
+        path = %file.path.display(),
+        bytes = file.size,
+        elapsed_ms,
+        skipped = file.skipped,
+        "downloaded"
+    );
+}
+
+/// Emits the event of the failure of the transfer of an item.
+#[cfg(feature = "tracing")]
+pub(crate) fn failed(span: &Span, error: &DownloadError) {
+    tracing::warn!(parent: span, error = %error, "download failed");
+}
+
+/// Records the status of the response in its request `span`.
+#[cfg(feature = "tracing")]
+pub(crate) fn response(span: &Span, status: StatusCode) {
+    span.record("status", status.as_u16());
+}
+
+/// Emits the event of an attempt which failed and is retried after `delay` in the current span.
+#[cfg(feature = "tracing")]
+pub(crate) fn retrying(attempt: u32, delay: Duration) {
+    tracing::debug!(attempt, delay_ms = delay.as_millis() as u64, "retrying");
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn download(_extractor: &'static str, _url: &str) -> Span {
+    Span
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn item(_item: usize, _url: &str) -> Span {
+    Span
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn request(_method: &Method, _url: &Url, _attempt: u32) -> Span {
+    Span
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn started() {}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn finished(_span: &Span, _file: &DownloadedFile) {}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn failed(_span: &Span, _error: &DownloadError) {}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn response(_span: &Span, _status: StatusCode) {}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn retrying(_attempt: u32, _delay: Duration) {}
+
+/// Stand-ins for the types of `tracing` when the feature is disabled.
+#[cfg(not(feature = "tracing"))]
+mod noop {
+    use std::future::Future;
+
+    /// A span which records nothing.
+    #[derive(Debug, Clone)]
+    pub(crate) struct Span;
+
+    /// Instruments nothing, the future being returned as is.
+    pub(crate) trait Instrument: Sized {
+        fn instrument(self, _span: Span) -> Self {
+            self
+        }
+    }
+
+    impl<T: Future> Instrument for T {}
+}
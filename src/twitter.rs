@@ -1,13 +1,25 @@
-use std::{ffi::OsStr, path::Path};
+use std::{ffi::OsStr, path::Path, time::Duration};
 
 use self::{
-    details::{MediaEntity, TweetDetails, VideoInfo},
+    details::{MediaEntity, TweetDetails, TweetResultValue, VideoInfo},
     utils::RequestDetails,
 };
 use crate::{
+    cancel::{self, CancellationToken},
+    errors::ErrorKind,
     header::HeaderMapBuilder,
+    http::{HttpConfig, HttpTransport, Transport},
+    info::{InfoJson, Media, MediaFormat, MediaInfo, Thumbnail},
+    overwrite::OverwritePolicy,
+    part_file::DEFAULT_PART_SUFFIX,
     prelude::{DownloadError, Downloader},
+    progress::ProgressHandler,
+    report::{DownloadReport, FileKind},
     resource::ResourceDownloader,
+    retry::RetryPolicy,
+    sanitize::FileNameSanitizer,
+    template::{OutputTemplate, TemplateFields},
+    trace::{self, Instrument},
     twitter::{details::MediaType, utils::retrieve_request_details},
 };
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
@@ -18,6 +30,9 @@ use serde::Deserialize;
 mod details;
 pub mod utils;
 
+/// Name of the extractor of the tweets, in errors and `.info.json` files.
+pub(crate) const EXTRACTOR: &str = "twitter";
+
 /*
 THIS MESSAGE IS COPY-PASTE FROM `https://github.com/inteoryx/twitter-video-dl.git` repository from which this [`TwitterDownloader`] is an implementation of.
 
@@ -61,8 +76,30 @@ pub struct TwitterDownloader {
     name_all: Option<String>,
     /// The name that should be given to the downloaded file if there is only one
     name_if_only_one_file: Option<String>,
-
-    print_download_status: bool,
+    /// The suffix of the files being downloaded.
+    part_suffix: String,
+    /// The policy used to retry failed requests and transfers.
+    retry_policy: RetryPolicy,
+    /// The policy applied when a file already exists.
+    overwrite_policy: OverwritePolicy,
+    /// The template of the paths of the files, relative to the download folder.
+    output_template: Option<OutputTemplate>,
+    /// The sanitizer of the names of the files.
+    sanitizer: FileNameSanitizer,
+    /// The client sending all the requests.
+    client: Client,
+    /// Configuration the client was built from.
+    http_config: HttpConfig,
+    /// Transport sending the requests instead of the client, if set.
+    transport: Option<Transport>,
+    /// Base URLs of the endpoints the requests are sent to.
+    endpoints: TwitterEndpoints,
+    /// The handler receiving the progress of the downloads.
+    progress: Option<ProgressHandler>,
+    /// The token cancelling the downloads.
+    cancellation: CancellationToken,
+    /// Whether a `.info.json` sidecar is written next to each file.
+    info_json: bool,
 }
 
 /// Represents the kind of media to download from Twitter.
@@ -74,6 +111,99 @@ pub enum MediaKind {
     Video,
 }
 
+/// Base URLs of the endpoints the [`TwitterDownloader`] sends its requests to, see [`TwitterDownloader::with_endpoints`].
+///
+/// They default to the ones of Twitter, and can point the downloader at a mock server in tests or at a caching mirror.
+/// The paths of the endpoints are appended to the base URLs, which may have a path themselves.
+/// The medias are downloaded from the URLs found in the details of the tweet.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::prelude::{TwitterDownloader, TwitterEndpoints};
+/// use url::Url;
+///
+/// let endpoints = TwitterEndpoints::new()
+///     .web(Url::parse("http://127.0.0.1:8080/twitter").unwrap())
+///     .api(Url::parse("http://127.0.0.1:8080/api").unwrap())
+///     .assets(Url::parse("http://127.0.0.1:8080/assets").unwrap());
+///
+/// let mut downloader = TwitterDownloader::new("https://x.com/user/status/1234567890").unwrap();
+/// downloader.with_endpoints(endpoints);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwitterEndpoints {
+    web: Url,
+    api: Url,
+    assets: Url,
+}
+
+impl Default for TwitterEndpoints {
+    fn default() -> Self {
+        Self {
+            web: Url::parse("https://twitter.com").unwrap(),
+            api: Url::parse("https://api.twitter.com").unwrap(),
+            assets: Url::parse("https://abs.twimg.com").unwrap(),
+        }
+    }
+}
+
+impl TwitterEndpoints {
+    /// Creates the endpoints of Twitter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the base URL of the tweet pages and of the GraphQL API, `https://twitter.com` by default.
+    pub fn web(mut self, base: Url) -> Self {
+        self.web = base;
+        self
+    }
+
+    /// Sets the base URL of the API activating guest tokens, `https://api.twitter.com` by default.
+    pub fn api(mut self, base: Url) -> Self {
+        self.api = base;
+        self
+    }
+
+    /// Sets the base URL of the scripts the bearer token is read from, `https://abs.twimg.com` by default.
+    pub fn assets(mut self, base: Url) -> Self {
+        self.assets = base;
+        self
+    }
+
+    /// Returns the URL of the page of the tweet at `url`.
+    fn tweet_page(&self, url: &Url) -> String {
+        join_url(&self.web, url.path())
+    }
+
+    /// Returns the pattern of the URL of the main script of the tweet pages.
+    fn main_js_pattern(&self) -> String {
+        format!(
+            r"{}/responsive-web/client-web-legacy/main\.[^.]+\.js",
+            regex::escape(self.assets.as_str().trim_end_matches('/'))
+        )
+    }
+
+    /// Returns the URL activating guest tokens.
+    fn guest_activation(&self) -> String {
+        join_url(&self.api, "/1.1/guest/activate.json")
+    }
+
+    /// Returns the URL of the GraphQL endpoint retrieving the details of a tweet.
+    fn tweet_details(&self) -> String {
+        join_url(
+            &self.web,
+            "/i/api/graphql/ncDeACNGIApPMaqGVuF_rw/TweetResultByRestId",
+        )
+    }
+}
+
+/// Appends `path` to the base URL `base`.
+fn join_url(base: &Url, path: &str) -> String {
+    format!("{}{}", base.as_str().trim_end_matches('/'), path)
+}
+
 #[derive(Debug, Deserialize)]
 struct GuestTokenResponse {
     /// The guest token for accessing Twitter content.
@@ -133,7 +263,18 @@ impl TwitterDownloader {
             },
             name_all: None,
             name_if_only_one_file: None,
-            print_download_status: false,
+            part_suffix: DEFAULT_PART_SUFFIX.to_owned(),
+            retry_policy: RetryPolicy::default(),
+            overwrite_policy: OverwritePolicy::default(),
+            output_template: None,
+            sanitizer: FileNameSanitizer::default(),
+            client: Client::new(),
+            http_config: HttpConfig::default(),
+            transport: None,
+            endpoints: TwitterEndpoints::default(),
+            progress: None,
+            cancellation: CancellationToken::new(),
+            info_json: false,
         })
     }
 
@@ -184,27 +325,90 @@ impl TwitterDownloader {
 
     /// Retrieves the media entities associated with the Twitter tweet.
     ///
-    /// This method asynchronously fetches and returns the media entities (such as videos and images) associated with the Twitter tweet.
+    /// This method asynchronously fetches and returns the media entities (such as videos and images) associated with the Twitter tweet,
+    /// along with the fields of the tweet available to output templates and the sidecar shared by its medias.
     ///
     /// ## Returns
     ///
-    /// Returns a [`Result`] containing a vector of [`MediaEntity]` instances on success, or a [`DownloadError`] if the retrieval fails.
-    async fn get_tweet_medias(&self) -> Result<Vec<MediaEntity>, DownloadError> {
-        let (bearer_token, guest_token) = self.get_tokens().await?;
-
-        let tweet_details = self.get_tweet_details(&bearer_token, &guest_token).await?;
+    /// Returns a [`Result`] containing a vector of [`MediaEntity]` instances, the fields and the sidecar on success, or a [`DownloadError`] if the retrieval fails.
+    async fn get_tweet_medias(
+        &self,
+    ) -> Result<(Vec<MediaEntity>, TemplateFields, InfoJson), DownloadError> {
+        let tweet = self.get_tweet().await?;
+        let fields = self.template_fields(&tweet);
+        let info_json = self.info_json(&tweet);
 
         // medias contain all the informations regarding the tweet videos and images
-        let opt_medias = tweet_details.data.tweet_result.result.legacy.entities.media;
+        let opt_medias = tweet.legacy.entities.media;
 
         let medias = opt_medias.ok_or_else(|| {
-            DownloadError::TwitterError(format!(
-                "The tweet with ID `{}` does not contain any associated media.",
-                self.tweet_id()
-            ))
+            DownloadError::new(
+                ErrorKind::NotFound,
+                format!(
+                    "The tweet with ID `{}` does not contain any associated media.",
+                    self.tweet_id()
+                ),
+            )
         })?;
 
-        Ok(medias)
+        Ok((medias, fields, info_json))
+    }
+
+    /// Asynchronously retrieves the details of the tweet.
+    async fn get_tweet(&self) -> Result<TweetResultValue, DownloadError> {
+        let (bearer_token, guest_token) = self.get_tokens().await?;
+        let tweet_details = self.get_tweet_details(&bearer_token, &guest_token).await?;
+
+        Ok(tweet_details.data.tweet_result.result)
+    }
+
+    /// Returns the fields of the tweet available to output templates.
+    fn template_fields(&self, tweet: &TweetResultValue) -> TemplateFields {
+        let user = &tweet.core.user_results.result.legacy;
+        let mut fields = TemplateFields::new();
+
+        fields
+            .set("id", self.tweet_id())
+            .set("title", &tweet.legacy.full_text)
+            .set("uploader", &user.screen_name)
+            .set("uploader_name", &user.name)
+            .set_opt("upload_date", compact_date(&tweet.legacy.created_at));
+
+        fields
+    }
+
+    /// Returns the sidecar of the medias of the tweet, holding its text, its counts and its author.
+    fn info_json(&self, tweet: &TweetResultValue) -> InfoJson {
+        let user = &tweet.core.user_results.result;
+        let legacy = &tweet.legacy;
+
+        let details = serde_json::json!({
+            "full_text": legacy.full_text,
+            "created_at": legacy.created_at,
+            "lang": legacy.lang,
+            "conversation_id": legacy.conversation_id_str,
+            "favorite_count": legacy.favorite_count,
+            "retweet_count": legacy.retweet_count,
+            "reply_count": legacy.reply_count,
+            "quote_count": legacy.quote_count,
+            "view_count": tweet.views.count.as_deref().and_then(|count| count.parse::<u64>().ok()),
+            "user": {
+                "id": user.rest_id,
+                "screen_name": user.legacy.screen_name,
+                "name": user.legacy.name,
+            },
+        });
+
+        InfoJson {
+            url: self.url.to_string(),
+            extractor: EXTRACTOR.to_owned(),
+            id: Some(self.tweet_id().to_owned()),
+            title: Some(legacy.full_text.to_owned()),
+            uploader: Some(user.legacy.screen_name.to_owned()),
+            upload_date: compact_date(&legacy.created_at),
+            details,
+            ..Default::default()
+        }
     }
 
     /// Extracts the status ID and tweet ID from the Twitter tweet URL.
@@ -218,10 +422,13 @@ impl TwitterDownloader {
             }
         }
 
-        Err(DownloadError::TwitterError(format!(
-            "Failed to parse status_id and tweet_id from the tweet URL: `{}`",
-            url
-        )))
+        Err(DownloadError::new(
+            ErrorKind::InvalidUrl,
+            format!(
+                "Failed to parse status_id and tweet_id from the tweet URL: `{}`",
+                url
+            ),
+        ))
     }
 
     /// Sets the downloader to download only images from the Twitter tweet.
@@ -236,6 +443,12 @@ impl TwitterDownloader {
         self
     }
 
+    /// Sets the base URLs of the endpoints the requests are sent to, see [`TwitterEndpoints`].
+    pub fn with_endpoints(&mut self, endpoints: TwitterEndpoints) -> &mut Self {
+        self.endpoints = endpoints;
+        self
+    }
+
     /// Returns the status ID of the Twitter tweet.
     pub fn status_id(&self) -> &str {
         &self.status_id
@@ -249,39 +462,56 @@ impl TwitterDownloader {
         self.url.as_str()
     }
 
+    /// Returns the transport sending the requests, the client of the downloader unless one was set.
+    fn transport(&self) -> &dyn HttpTransport {
+        match &self.transport {
+            Some(transport) => transport,
+            None => &self.client,
+        }
+    }
+
     /// Fetches the content of the Twitter tweet page asynchronously.
-    async fn fetch_page_content(url: &str) -> Result<String, DownloadError> {
-        let response = reqwest::get(url).await?;
+    async fn fetch_page_content(&self, url: &str) -> Result<String, DownloadError> {
+        let response = self
+            .retry_policy
+            .send(self.transport(), || self.client.get(url))
+            .await?;
 
         if !response.status().is_success() {
-            return Err(DownloadError::TwitterError(format!(
-                "Failed to fetch content from URL: {}",
-                url
-            )));
+            return Err(DownloadError::new(
+                ErrorKind::from_status(response.status()),
+                format!("Failed to fetch content from URL: {}", url),
+            ));
         }
 
         response.text().await.map_err(|_| {
-            DownloadError::TwitterError(format!("Failed to read text from URL: {}", url))
+            DownloadError::new(
+                ErrorKind::Network,
+                format!("Failed to read text from URL: {}", url),
+            )
         })
     }
 
     /// Asynchronously retrieves the URL of the main JavaScript file from the Twitter tweet page.
     async fn get_mainjs_url(&self) -> Result<String, DownloadError> {
-        let content = Self::fetch_page_content(self.url_str()).await?;
+        let content = self
+            .fetch_page_content(&self.endpoints.tweet_page(&self.url))
+            .await?;
 
-        let main_js_regex =
-            Regex::new(r"https://abs.twimg.com/responsive-web/client-web-legacy/main\.[^.]+\.js")
-                .unwrap();
+        let main_js_regex = Regex::new(&self.endpoints.main_js_pattern()).unwrap();
         let mainjs_urls: Vec<&str> = main_js_regex
             .find_iter(&content)
             .map(|mat| mat.as_str())
             .collect();
 
         if mainjs_urls.is_empty() {
-            return Err(DownloadError::TwitterError(format!(
-                "Failed to retrieve `main.js` file from `{}` page.",
-                self.url
-            )));
+            return Err(DownloadError::new(
+                ErrorKind::Extraction,
+                format!(
+                    "Failed to retrieve `main.js` file from `{}` page.",
+                    self.url
+                ),
+            ));
         }
 
         Ok(mainjs_urls[0].to_owned())
@@ -289,7 +519,7 @@ impl TwitterDownloader {
 
     /// Asynchronously retrieves the bearer token from the main JavaScript file URL.
     async fn get_bearer_token(&self, mainjs_url: &str) -> Result<String, DownloadError> {
-        let main_js_content = Self::fetch_page_content(mainjs_url).await?;
+        let main_js_content = self.fetch_page_content(mainjs_url).await?;
 
         let bearer_regex = Regex::new(r#"AAAAAAAAA[^\"']+"#).unwrap();
         let bearer_tokens: Vec<&str> = bearer_regex
@@ -298,10 +528,10 @@ impl TwitterDownloader {
             .collect();
 
         if bearer_tokens.is_empty() {
-            return Err(DownloadError::TwitterError(format!(
-                "Failed to find bearer token from `{}` page",
-                self.url
-            )));
+            return Err(DownloadError::new(
+                ErrorKind::Extraction,
+                format!("Failed to find bearer token from `{}` page", self.url),
+            ));
         }
 
         let bearer_token = bearer_tokens[0];
@@ -311,8 +541,6 @@ impl TwitterDownloader {
 
     /// Asynchronously retrieves the guest token using the provided bearer token.
     async fn get_guest_token(&self, bearer_token: &str) -> Result<String, DownloadError> {
-        let client = Client::new();
-
         let headers = HeaderMapBuilder::new()
             .with_user_agent()
             .accept("*/*")
@@ -324,17 +552,22 @@ impl TwitterDownloader {
             )
             .build();
 
-        let body = client
-            .post("https://api.twitter.com/1.1/guest/activate.json")
-            .headers(headers)
-            .send()
+        let guest_activation_url = self.endpoints.guest_activation();
+
+        let body = self
+            .retry_policy
+            .send(self.transport(), || {
+                self.client
+                    .post(&guest_activation_url)
+                    .headers(headers.clone())
+            })
             .await
             .map(|res| {
                 if !res.status().is_success() {
-                    return Err(DownloadError::TwitterError(format!(
-                        "Failed to find guest token from `{}` page",
-                        self.url
-                    )));
+                    return Err(DownloadError::new(
+                        ErrorKind::from_status(res.status()),
+                        format!("Failed to find guest token from `{}` page", self.url),
+                    ));
                 }
 
                 Ok(res)
@@ -345,10 +578,10 @@ impl TwitterDownloader {
         serde_json::from_str::<GuestTokenResponse>(&body)
             .map(|token_response| token_response.guest_token)
             .map_err(|_| {
-                DownloadError::TwitterError(format!(
-                    "Failed to find guest token from `{}` page",
-                    self.url
-                ))
+                DownloadError::new(
+                    ErrorKind::Extraction,
+                    format!("Failed to find guest token from `{}` page", self.url),
+                )
             })
     }
 
@@ -379,7 +612,12 @@ impl TwitterDownloader {
         let variables_encoded =
             utf8_percent_encode(&variables_string, NON_ALPHANUMERIC).to_string();
 
-        let url = format!("https://twitter.com/i/api/graphql/ncDeACNGIApPMaqGVuF_rw/TweetResultByRestId?variables={}&features={}", variables_encoded, features_encoded);
+        let url = format!(
+            "{}?variables={}&features={}",
+            self.endpoints.tweet_details(),
+            variables_encoded,
+            features_encoded
+        );
 
         Ok(url)
     }
@@ -391,7 +629,6 @@ impl TwitterDownloader {
         guest_token: &str,
     ) -> Result<Response, DownloadError> {
         let url = self.get_details_url().await?;
-        let client = Client::new();
 
         let headers = HeaderMapBuilder::new()
             .with_user_agent()
@@ -408,7 +645,12 @@ impl TwitterDownloader {
             )
             .build();
 
-        let details = client.get(url).headers(headers).send().await?;
+        let details = self
+            .retry_policy
+            .send(self.transport(), || {
+                self.client.get(&url).headers(headers.clone())
+            })
+            .await?;
         Ok(details)
     }
 
@@ -425,15 +667,21 @@ impl TwitterDownloader {
         // should we update the loop to automatically add new variables if needed when the variables changes server side ??
 
         if !details.status().is_success() {
-            return Err(DownloadError::TwitterError(format!(
-                "Failed to get details of tweet with id `{}`",
-                self.tweet_id()
-            )));
+            return Err(DownloadError::new(
+                ErrorKind::from_status(details.status()),
+                format!(
+                    "Failed to get details of tweet with id `{}`",
+                    self.tweet_id()
+                ),
+            ));
         }
 
         let response_text = details.text().await?;
         let tweet_details = serde_json::from_str(&response_text).map_err(|_e| {
-            DownloadError::TwitterError("Failed to parse tweet details.".to_owned())
+            DownloadError::new(
+                ErrorKind::Extraction,
+                "Failed to parse tweet details.".to_owned(),
+            )
         })?;
 
         Ok(tweet_details)
@@ -477,62 +725,64 @@ impl TwitterDownloader {
     // {
     //     Self::blocking(async { self.download_as_tweets_folder_to(path).await })
     // }
-}
-
-#[async_trait::async_trait]
-impl Downloader for TwitterDownloader {
-    fn is_valid_url(url: &Url) -> bool {
-        url.domain() == Some("twitter.com")
-            || url.domain() == Some("x.com")
-            || url.domain() == Some("www.twitter.com")
-            || url.domain() == Some("www.x.com")
-    }
-
-    fn get_dl_status(&mut self) -> &mut bool {
-        &mut self.print_download_status
-    }
 
-    async fn download_to<P: AsRef<Path> + std::marker::Send>(
-        &self,
-        folder_path: P,
-    ) -> Result<(), DownloadError> {
-        let path = folder_path.as_ref();
-        let medias = self.get_tweet_medias().await?;
+    /// Downloads the medias of the tweet to the folder at `path`.
+    async fn download_tweet_to(&self, path: &Path) -> Result<DownloadReport, DownloadError> {
+        let (medias, fields, info_json) =
+            cancel::or_cancelled(&self.cancellation, self.get_tweet_medias()).await?;
 
         let media_infos = medias
             .iter()
             .map(|media_entity| {
-                media_entity.try_into().map_err(|e| {
-                    DownloadError::TwitterError(format!(
-                        "{} in `{}` tweet details.",
-                        e,
-                        self.tweet_id()
-                    ))
-                })
+                let media = media_entity.try_into().map_err(|e| {
+                    DownloadError::new(
+                        ErrorKind::Extraction,
+                        format!("{} in `{}` tweet details.", e, self.tweet_id()),
+                    )
+                })?;
+
+                Ok((media_entity, media))
             })
-            .collect::<Result<Vec<TwitterMedia>, DownloadError>>()?;
+            .collect::<Result<Vec<(&MediaEntity, TwitterMedia)>, DownloadError>>()?;
 
-        let download_links: Vec<TwitterMedia> = media_infos
+        let download_links: Vec<(&MediaEntity, TwitterMedia)> = media_infos
             .into_iter()
-            .filter(|x| TwitterMedia::filter_media_kind(x, self.only_media_kind.as_ref()))
+            .filter(|(_, x)| TwitterMedia::filter_media_kind(x, self.only_media_kind.as_ref()))
             .collect();
 
-        if self.print_download_status {
-            println!("Downloading...");
-        }
+        trace::started();
 
         tokio::fs::create_dir_all(path).await?;
 
         let number_of_files = download_links.len();
+        let fields = &fields;
+        let info_json = &info_json;
 
         let results = futures::future::join_all(download_links.into_iter().enumerate().map(
-            |(index, media)| async move {
+            |(index, (media_entity, media))| async move {
                 let url = media.url();
+                let (kind, media_duration) = match media {
+                    TwitterMedia::Image { .. } => (FileKind::Image, None),
+                    TwitterMedia::Video { infos } => (
+                        FileKind::Video,
+                        infos
+                            .duration_millis
+                            .map(|millis| Duration::from_millis(millis.into())),
+                    ),
+                };
 
                 let mut rsrc_downloader = ResourceDownloader::new(url).map_err(|_| {
-                    DownloadError::TwitterError(format!("Invalid Media File path: `{}`", url))
+                    DownloadError::new(
+                        ErrorKind::InvalidUrl,
+                        format!("Invalid Media File path: `{}`", url),
+                    )
                 })?;
 
+                let mut media_fields = fields.clone();
+                media_fields
+                    .set("index", index + 1)
+                    .set_opt("ext", media.extension().map(|ext| ext.to_string_lossy()));
+
                 let filename = if self.name_if_only_one_file.is_some() && number_of_files == 1 {
                     self.name_if_only_one_file.as_ref().unwrap().to_owned()
                 } else if let Some(name) = self.name_all.as_ref() {
@@ -548,34 +798,160 @@ impl Downloader for TwitterDownloader {
                 };
 
                 rsrc_downloader.with_name(filename);
+                rsrc_downloader.with_template_fields(media_fields);
+
+                if let Some(template) = &self.output_template {
+                    rsrc_downloader.with_output_template(template.clone());
+                }
 
-                let download_result = rsrc_downloader.download_to(&path).await;
+                rsrc_downloader.with_part_suffix(&self.part_suffix);
+                rsrc_downloader.with_retry_policy(self.retry_policy.clone());
+                rsrc_downloader.with_overwrite_policy(self.overwrite_policy);
+                rsrc_downloader.with_file_name_sanitizer(self.sanitizer.clone());
+                rsrc_downloader.with_client(self.client.clone());
+                *rsrc_downloader.get_transport() = self.transport.clone();
+                rsrc_downloader.with_progress_item(index);
+                rsrc_downloader.with_cancellation(self.cancellation.clone());
+
+                if let Some(progress) = &self.progress {
+                    rsrc_downloader.with_progress(progress.clone());
+                }
 
-                if self.print_download_status {
-                    if let Err(err) = &download_result {
-                        eprintln!("Error downloading with url `{}`: {:?}", url, err);
-                    } else {
-                        println!("Media downloaded successfully: {}", url);
+                // the resource downloader only knows the kind of the media from its content type
+                let report = rsrc_downloader
+                    .download_to(path)
+                    .await?
+                    .into_iter()
+                    .map(|mut file| {
+                        file.kind = kind;
+                        file.media_duration = media_duration;
+                        file
+                    })
+                    .collect::<DownloadReport>();
+
+                if self.info_json {
+                    for file in report.files().iter().filter(|file| !file.skipped) {
+                        let mut info_json = info_json.clone();
+                        info_json.format = media_info(media_entity)
+                            .formats
+                            .into_iter()
+                            .find(|format| format.url == url);
+
+                        info_json.write(file).await?;
                     }
                 }
 
-                download_result
+                Ok(report)
             },
         ))
         .await;
 
-        for result in results {
-            result?
-        }
+        DownloadReport::from_results(results)
+    }
+
+    /// Retrieves the information of the tweet, see [`Downloader::info`].
+    async fn tweet_info(&self) -> Result<MediaInfo, DownloadError> {
+        let tweet = cancel::or_cancelled(&self.cancellation, self.get_tweet()).await?;
+        let user = &tweet.core.user_results.result.legacy;
+        let medias = tweet.legacy.entities.media.as_deref().unwrap_or_default();
+
+        let duration = medias
+            .iter()
+            .find_map(|media| media.video_info.as_ref()?.duration_millis)
+            .map(|millis| f64::from(millis) / 1000.0);
+
+        let thumbnails = medias
+            .iter()
+            .map(|media| Thumbnail {
+                url: media.media_url_https.to_owned(),
+                width: Some(media.original_info.width as u64),
+                height: Some(media.original_info.height as u64),
+            })
+            .collect();
+
+        Ok(MediaInfo {
+            id: Some(self.tweet_id().to_owned()),
+            url: self.url.to_string(),
+            title: Some(tweet.legacy.full_text.to_owned()),
+            uploader: Some(user.screen_name.to_owned()),
+            upload_date: compact_date(&tweet.legacy.created_at),
+            duration,
+            description: Some(tweet.legacy.full_text.to_owned()),
+            thumbnails,
+            media: medias.iter().map(media_info).collect(),
+            entries: Vec::new(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Downloader for TwitterDownloader {
+    fn is_valid_url(url: &Url) -> bool {
+        url.domain() == Some("twitter.com")
+            || url.domain() == Some("x.com")
+            || url.domain() == Some("www.twitter.com")
+            || url.domain() == Some("www.x.com")
+    }
+
+    fn get_part_suffix(&mut self) -> &mut String {
+        &mut self.part_suffix
+    }
 
-        Ok(())
+    fn get_retry_policy(&mut self) -> &mut RetryPolicy {
+        &mut self.retry_policy
+    }
+
+    fn get_client(&mut self) -> &mut Client {
+        &mut self.client
+    }
+
+    fn get_http_config(&mut self) -> &mut HttpConfig {
+        &mut self.http_config
+    }
+
+    fn get_transport(&mut self) -> &mut Option<Transport> {
+        &mut self.transport
+    }
+
+    fn get_progress_handler(&mut self) -> &mut Option<ProgressHandler> {
+        &mut self.progress
+    }
+
+    fn get_output_template(&mut self) -> &mut Option<OutputTemplate> {
+        &mut self.output_template
+    }
+
+    fn get_file_name_sanitizer(&mut self) -> &mut FileNameSanitizer {
+        &mut self.sanitizer
+    }
+
+    fn get_overwrite_policy(&mut self) -> &mut OverwritePolicy {
+        &mut self.overwrite_policy
+    }
+
+    fn get_cancellation_token(&mut self) -> &mut CancellationToken {
+        &mut self.cancellation
+    }
+
+    fn get_info_json(&mut self) -> &mut bool {
+        &mut self.info_json
+    }
+
+    async fn download_to<P: AsRef<Path> + std::marker::Send>(
+        &self,
+        folder_path: P,
+    ) -> Result<DownloadReport, DownloadError> {
+        self.download_tweet_to(folder_path.as_ref())
+            .instrument(trace::download(EXTRACTOR, self.url.as_str()))
+            .await
+            .map_err(|err| err.with_context(EXTRACTOR, self.url.as_str()))
     }
 
     /// Downloads and saves the twitter file(s) to the current working directory.
     ///
     /// ## Returns
     ///
-    /// Returns a future representing the download operation, which resolves to a [`Result`] indicating success or failure.
+    /// Returns a future representing the download operation, which resolves to a [`DownloadReport`] listing the written files on success.
     ///
     /// ## Examples
     ///
@@ -590,17 +966,26 @@ impl Downloader for TwitterDownloader {
     ///     Ok(())
     /// }
     /// ```
-    async fn download(&self) -> Result<(), DownloadError> {
+    async fn download(&self) -> Result<DownloadReport, DownloadError> {
         self.download_to("./").await
     }
 
+    /// Retrieves the information of the tweet, each of its images and videos being a media.
+    ///
+    /// The title and the description are the text of the tweet, and the uploader is the screen name of its author.
+    async fn info(&self) -> Result<MediaInfo, DownloadError> {
+        self.tweet_info()
+            .await
+            .map_err(|err| err.with_context(EXTRACTOR, self.url.as_str()))
+    }
+
     /// Blocks the current thread until the download completes, using asynchronous execution.
     ///
     /// The tweeter files are saved to the default location (`./`).
     ///
     /// ## Returns
     ///
-    /// Returns a [`Result`] indicating success or failure of the download operation.
+    /// Returns a [`DownloadReport`] listing the written files on success.
     ///
     /// ## Examples
     ///
@@ -614,7 +999,7 @@ impl Downloader for TwitterDownloader {
     ///     Ok(())
     /// }
     /// ```
-    fn blocking_download(&self) -> Result<(), DownloadError>
+    fn blocking_download(&self) -> Result<DownloadReport, DownloadError>
     where
         Self: Sync,
     {
@@ -629,7 +1014,7 @@ impl Downloader for TwitterDownloader {
     ///
     /// ## Returns
     ///
-    /// Returns a `Result` indicating success or failure of the download operation.
+    /// Returns a [`DownloadReport`] listing the written files on success.
     ///
     /// ## Example
     ///
@@ -646,7 +1031,7 @@ impl Downloader for TwitterDownloader {
     fn blocking_download_to<P: AsRef<Path> + std::marker::Send>(
         &self,
         path: P,
-    ) -> Result<(), DownloadError>
+    ) -> Result<DownloadReport, DownloadError>
     where
         Self: Sync,
     {
@@ -654,6 +1039,78 @@ impl Downloader for TwitterDownloader {
     }
 }
 
+/// Converts the creation date of a tweet, e.g. `Wed Oct 10 20:19:24 +0000 2018`, to `YYYYMMDD`.
+fn compact_date(created_at: &str) -> Option<String> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let parts: Vec<&str> = created_at.split_whitespace().collect();
+    let [_, month, day, _, _, year] = parts.as_slice() else {
+        return None;
+    };
+
+    let month = MONTHS.iter().position(|m| m == month)? + 1;
+    let day: u32 = day.parse().ok()?;
+
+    Some(format!("{}{:02}{:02}", year, month, day))
+}
+
+/// Returns the information of a media of a tweet, the formats of a video being its variants.
+fn media_info(media: &MediaEntity) -> Media {
+    let video_info = match (&media._type, &media.video_info) {
+        (MediaType::Video | MediaType::Gif, Some(video_info)) => video_info,
+        _ => {
+            let format = MediaFormat {
+                format_id: None,
+                url: media.media_url_https.to_owned(),
+                kind: FileKind::Image,
+                ext: Path::new(&media.media_url_https)
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().into_owned()),
+                mime_type: None,
+                width: Some(media.original_info.width as u64),
+                height: Some(media.original_info.height as u64),
+                bitrate: None,
+                size: None,
+            };
+
+            return Media {
+                kind: FileKind::Image,
+                formats: vec![format],
+            };
+        }
+    };
+
+    let formats = video_info
+        .variants
+        .iter()
+        .map(|variant| MediaFormat {
+            format_id: None,
+            url: variant.url.to_owned(),
+            kind: FileKind::Video,
+            ext: variant
+                .content_type
+                .split('/')
+                .nth(1)
+                .map(|subtype| match subtype {
+                    "x-mpegURL" => "m3u8".to_owned(),
+                    subtype => subtype.to_owned(),
+                }),
+            mime_type: Some(variant.content_type.to_owned()),
+            width: None,
+            height: None,
+            bitrate: variant.bitrate,
+            size: None,
+        })
+        .collect();
+
+    Media {
+        kind: FileKind::Video,
+        formats,
+    }
+}
+
 /// Represents a media file from Twitter, such as an image or video.
 #[derive(Debug, Clone, Copy)]
 pub enum TwitterMedia<'a> {
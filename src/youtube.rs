@@ -1,16 +1,33 @@
+use crate::cancel::{self, CancellationToken};
+use crate::errors::ErrorKind;
 use crate::header::HeaderMapBuilder;
+use crate::http::{HttpConfig, HttpTransport, Transport};
+use crate::info::{InfoJson, Media, MediaFormat, MediaInfo, Thumbnail};
+use crate::overwrite::{Destination, OverwritePolicy};
+use crate::part_file::{PartFile, DEFAULT_PART_SUFFIX};
 use crate::prelude::{DownloadError, Downloader};
+use crate::progress::{self, ProgressHandler, ProgressTracker};
+use crate::report::{DownloadReport, DownloadedFile, FileKind};
+use crate::retry::{self, RetryPolicy};
+use crate::sanitize::FileNameSanitizer;
+use crate::template::{OutputTemplate, TemplateFields};
+use crate::trace::{self, Instrument};
 use crate::youtube::initial_data::PlaylistVideoRenderer;
 use reqwest::{Client, Url};
-use rusty_ytdl::FFmpegArgs;
-use rusty_ytdl::{VideoOptions, VideoQuality};
+use rusty_ytdl::{FFmpegArgs, VideoFormat};
+use rusty_ytdl::{RequestOptions, VideoOptions, VideoQuality};
 use scraper::{Html, Selector};
 use serde_json::Value;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
 
 mod initial_data;
 mod video_data;
 
+/// Name of the extractor of the videos, in errors and `.info.json` files.
+pub(crate) const EXTRACTOR: &str = "youtube";
+
 pub use rusty_ytdl::{Video, VideoDetails, VideoInfo, VideoSearchOptions};
 
 pub use self::video_data::VideoData;
@@ -18,6 +35,72 @@ pub use self::video_data::VideoData;
 /// Returns true if the video should be downloaded, otherwise returns false.
 pub type PlaylistFilter = fn(&VideoData) -> bool;
 
+/// Base URL of the pages the [`YoutubeDownloader`] scrapes, see [`YoutubeDownloader::with_endpoints`].
+///
+/// It defaults to `https://www.youtube.com`, and can point the downloader at a mock server in tests or at a caching mirror.
+/// The paths of the pages are appended to the base URL, which may have a path itself.
+/// The information and the streams of the videos are still retrieved from YouTube by [`rusty_ytdl`].
+///
+/// ## Examples
+///
+/// ```no_run
+/// use rusty_dl::prelude::{YoutubeDownloader, YoutubeEndpoints};
+/// use url::Url;
+///
+/// let endpoints = YoutubeEndpoints::new().web(Url::parse("http://127.0.0.1:8080/youtube").unwrap());
+///
+/// let mut downloader = YoutubeDownloader::new("https://www.youtube.com/playlist?list=playlist_id").unwrap();
+/// downloader.with_endpoints(endpoints);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YoutubeEndpoints {
+    web: Url,
+}
+
+impl Default for YoutubeEndpoints {
+    fn default() -> Self {
+        Self {
+            web: Url::parse("https://www.youtube.com").unwrap(),
+        }
+    }
+}
+
+impl YoutubeEndpoints {
+    /// Creates the endpoints of YouTube.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the base URL of the video and playlist pages, `https://www.youtube.com` by default.
+    pub fn web(mut self, base: Url) -> Self {
+        self.web = base;
+        self
+    }
+
+    /// Returns the URL of the page at `url`, short `youtu.be` links pointing to the watch page of their video.
+    fn page(&self, url: &Url) -> String {
+        let short_link = matches!(url.domain(), Some("youtu.be" | "www.youtu.be"));
+
+        match url.path_segments().and_then(|mut segments| segments.next()) {
+            Some(video_id) if short_link => self.watch_page(video_id),
+            _ => match url.query() {
+                Some(query) => format!("{}?{}", self.join(url.path()), query),
+                None => self.join(url.path()),
+            },
+        }
+    }
+
+    /// Returns the URL of the watch page of the video with the given ID.
+    fn watch_page(&self, video_id: &str) -> String {
+        format!("{}?v={}", self.join("/watch"), video_id)
+    }
+
+    /// Appends `path` to the base URL.
+    fn join(&self, path: &str) -> String {
+        format!("{}{}", self.web.as_str().trim_end_matches('/'), path)
+    }
+}
+
 #[derive(Clone)]
 /// Implementation of a YouTube downloader.
 pub struct YoutubeDownloader {
@@ -30,7 +113,30 @@ pub struct YoutubeDownloader {
     is_playlist: bool,
     playlist_video_filter: Option<PlaylistFilter>,
 
-    print_download_status: bool,
+    /// The suffix of the files being downloaded.
+    part_suffix: String,
+    /// The policy used to retry failed requests and transfers.
+    retry_policy: RetryPolicy,
+    /// The policy applied when a file already exists.
+    overwrite_policy: OverwritePolicy,
+    /// The template of the paths of the files, relative to the download folder.
+    output_template: Option<OutputTemplate>,
+    /// The sanitizer of the names of the files.
+    sanitizer: FileNameSanitizer,
+    /// The client sending the requests retrieving playlists and titles.
+    client: Client,
+    /// Configuration the client was built from.
+    http_config: HttpConfig,
+    /// Transport sending the requests instead of the client, if set.
+    transport: Option<Transport>,
+    /// Base URLs of the pages the requests are sent to.
+    endpoints: YoutubeEndpoints,
+    /// The handler receiving the progress of the downloads.
+    progress: Option<ProgressHandler>,
+    /// The token cancelling the downloads.
+    cancellation: CancellationToken,
+    /// Whether a `.info.json` sidecar is written next to each video.
+    info_json: bool,
 }
 
 impl YoutubeDownloader {
@@ -75,7 +181,18 @@ impl YoutubeDownloader {
             is_playlist,
             playlist_video_filter: None,
 
-            print_download_status: false,
+            part_suffix: DEFAULT_PART_SUFFIX.to_owned(),
+            retry_policy: RetryPolicy::default(),
+            overwrite_policy: OverwritePolicy::default(),
+            output_template: None,
+            sanitizer: FileNameSanitizer::default(),
+            client: Client::new(),
+            http_config: HttpConfig::default(),
+            transport: None,
+            endpoints: YoutubeEndpoints::default(),
+            progress: None,
+            cancellation: CancellationToken::new(),
+            info_json: false,
         })
     }
 
@@ -107,20 +224,30 @@ impl YoutubeDownloader {
     /// }
     /// ```    
     pub fn get_video(&self) -> Result<Video, DownloadError> {
-        let filter = self.filter.to_owned();
-
-        let video_options = VideoOptions {
-            quality: VideoQuality::Highest,
-            filter,
-            ..Default::default()
-        };
+        let video_options = self.video_options();
 
         let video = rusty_ytdl::Video::new_with_options(self.url.as_str(), video_options)
-            .map_err(|_| DownloadError::VideoNotFound("Video Not Found".to_owned()))?;
+            .map_err(|_| DownloadError::new(ErrorKind::NotFound, "Video Not Found"))?;
 
         Ok(video)
     }
 
+    /// Builds the options of the videos to download, the requests of [`rusty_ytdl`] being sent through the client of the transport
+    /// if it has one, through the client of the downloader otherwise.
+    fn video_options(&self) -> VideoOptions {
+        let client = self.transport().client().unwrap_or(&self.client);
+
+        VideoOptions {
+            quality: VideoQuality::Highest,
+            filter: self.filter.to_owned(),
+            request_options: RequestOptions {
+                client: Some(client.clone()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
     /// Retrieves information about the video with a given URL or ID.
     ///
     /// This method returns a [`Result`] containing a [`Video`] instance, which represents the video and allows accessing its
@@ -138,16 +265,10 @@ impl YoutubeDownloader {
     ///
     /// Returns a [`DownloadError`] if the video is not found.
     fn get_video_with_url_or_id(&self, url_or_id: &str) -> Result<Video, DownloadError> {
-        let filter = self.filter.to_owned();
-
-        let video_options = VideoOptions {
-            quality: VideoQuality::Highest,
-            filter,
-            ..Default::default()
-        };
+        let video_options = self.video_options();
 
         let video = rusty_ytdl::Video::new_with_options(url_or_id, video_options)
-            .map_err(|_| DownloadError::VideoNotFound("Video Not Found".to_owned()))?;
+            .map_err(|_| DownloadError::new(ErrorKind::NotFound, "Video Not Found"))?;
 
         Ok(video)
     }
@@ -164,30 +285,37 @@ impl YoutubeDownloader {
     /// Returns a [`DownloadError`] if any error occurs during the retrieval process, such as failure to send HTTP requests,
     /// receiving unexpected responses, or parsing HTML content.
     async fn get_playlist(&self) -> Result<Playlist, DownloadError> {
-        let client = Client::new();
-
-        let response = client
-            .get(self.url.as_str())
-            .headers(HeaderMapBuilder::new().with_user_agent().build())
-            .send()
-            .await?
-            .text()
+        let response = self
+            .send_get_request(&self.endpoints.page(&self.url))
             .await?;
+        let response = DownloadError::check_status(response).await?.text().await?;
 
         let (name, videos) = self.scrape_videos_data(response)?;
         Ok(Playlist { name, videos })
     }
 
-    async fn get_video_title(url: &str) -> Result<String, DownloadError> {
-        let client = Client::new();
+    /// Returns the transport sending the requests, the client of the downloader unless one was set.
+    fn transport(&self) -> &dyn HttpTransport {
+        match &self.transport {
+            Some(transport) => transport,
+            None => &self.client,
+        }
+    }
 
-        let response = client
-            .get(url)
-            .headers(HeaderMapBuilder::new().with_user_agent().build())
-            .send()
-            .await?
-            .text()
-            .await?;
+    /// Sends a GET request to the given URL, retrying it according to the retry policy.
+    async fn send_get_request(&self, url: &str) -> Result<reqwest::Response, DownloadError> {
+        let headers = HeaderMapBuilder::new().with_user_agent().build();
+
+        self.retry_policy
+            .send(self.transport(), || {
+                self.client.get(url).headers(headers.clone())
+            })
+            .await
+    }
+
+    async fn get_video_title(&self, url: &str) -> Result<String, DownloadError> {
+        let response = self.send_get_request(url).await?;
+        let response = DownloadError::check_status(response).await?.text().await?;
 
         let document = Html::parse_document(&response);
         let scripts_selector = Selector::parse("title").unwrap();
@@ -214,8 +342,9 @@ impl YoutubeDownloader {
             .trim_end_matches(';')
             .to_string();
 
-        let parsed_value: Value = serde_json::from_str(&string_object)
-            .map_err(|_| DownloadError::YoutubeError(format!("Failed to scrape playlist data.")))?;
+        let parsed_value: Value = serde_json::from_str(&string_object).map_err(|_| {
+            DownloadError::new(ErrorKind::Extraction, "Failed to scrape playlist data.")
+        })?;
 
         Ok(parsed_value)
     }
@@ -311,6 +440,12 @@ impl YoutubeDownloader {
         self.playlist_video_filter = Some(filter)
     }
 
+    /// Sets the base URL of the pages the downloader scrapes, see [`YoutubeEndpoints`].
+    pub fn with_endpoints(&mut self, endpoints: YoutubeEndpoints) -> &mut Self {
+        self.endpoints = endpoints;
+        self
+    }
+
     /// Downloads a video to the specified path.
     ///
     /// **This function is not meant to be used  directly by users. Instead it should be called through one of the other functions in this struct.**
@@ -323,60 +458,192 @@ impl YoutubeDownloader {
     /// ## Arguments
     ///
     /// * `video` - The `[Video`] instance representing the video to be downloaded.
-    /// * `path` - The path of the file the video must be piped into.
+    /// * `path` - The path of the file the video must be piped into, which extension depends on the downloaded format.
+    /// * `item` - The index of the video in the progress events, i.e. its index in the playlist or `0`.
+    /// * `media_duration` - The duration of the video if known, reported along with the written file.
+    /// * `info` - The information of the video if known, written to its sidecar if enabled.
     ///
     /// ## Errors
     ///
     /// Returns a [`DownloadError`] if any error occurs during the download process, such as failure to create directories,
     /// fetching video information, or downloading the video file.
-    async fn download_video_to_path<P: AsRef<Path>>(
+    async fn download_video_to_path(
         &self,
         video: Video,
-        path: P,
-    ) -> Result<(), DownloadError> {
-        let mut file_path = path.as_ref().to_owned();
+        path: VideoPath<'_>,
+        item: usize,
+        media_duration: Option<Duration>,
+        info: Option<&VideoInfo>,
+    ) -> Result<DownloadedFile, DownloadError> {
+        let url = video.get_video_url();
+        let started_at = Instant::now();
+        let span = trace::item(item, &url);
+
+        // a transfer cut midway restarts from the beginning, the partial file being overwritten
+        let attempts = self.retry_policy.run(retry::is_transient_error, || {
+            self.stream_video_to_path(&video, &path, item)
+        });
+        let result = cancel::or_cancelled(&self.cancellation, attempts)
+            .instrument(span.clone())
+            .await;
+
+        match result {
+            Ok(mut file) => {
+                progress::finished(
+                    self.progress.as_ref(),
+                    item,
+                    &url,
+                    file.path.clone(),
+                    file.size,
+                );
+
+                file.media_duration = media_duration;
+                file.elapsed = started_at.elapsed();
+                trace::finished(&span, &file);
+
+                if self.info_json && !file.skipped {
+                    self.info_json(&video, info, &file).write(&file).await?;
+                }
+
+                Ok(file)
+            }
+            Err(err) => {
+                trace::failed(&span, &err);
+                progress::failed(self.progress.as_ref(), item, &url, &err);
+
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns the kind of the files written according to the filter of the downloader.
+    fn file_kind(&self) -> FileKind {
+        match self.filter {
+            VideoSearchOptions::Audio => FileKind::Audio,
+            _ => FileKind::Video,
+        }
+    }
+
+    /// Returns the file kept in place of the video, its download being skipped.
+    fn skipped_file(&self, path: PathBuf, url: &str, size: u64) -> DownloadedFile {
+        let mut file = DownloadedFile::new(path, url, size, self.file_kind());
+        file.skipped = true;
+
+        file
+    }
+
+    /// Returns the size of the format of the video that is downloaded, if advertised.
+    ///
+    /// The size of the `mp3` files converted by `ffmpeg` is never known beforehand.
+    async fn remote_size(&self, video: &Video) -> Result<Option<u64>, DownloadError> {
+        if let VideoSearchOptions::Audio = self.filter {
+            return Ok(None);
+        }
+
+        let info = video.get_info().await?;
+        let size = rusty_ytdl::choose_format(&info.formats, &self.video_options())
+            .ok()
+            .and_then(|format| format.content_length)
+            .and_then(|length| length.parse().ok());
+
+        Ok(size)
+    }
+
+    /// Streams the video into the file at `path`, which extension is set according to the downloaded format.
+    ///
+    /// The download is skipped if the file already exists and the overwrite policy says so,
+    /// before the video is streamed or converted by `ffmpeg`.
+    async fn stream_video_to_path(
+        &self,
+        video: &Video,
+        path: &VideoPath<'_>,
+        item: usize,
+    ) -> Result<DownloadedFile, DownloadError> {
+        let extension = match &self.filter {
+            VideoSearchOptions::VideoAudio | VideoSearchOptions::Video => Some("mp4"),
+            VideoSearchOptions::Audio => Some("mp3"),
+            VideoSearchOptions::Custom(_) => None,
+        };
+
+        let file_path = path.with_extension(extension, &self.sanitizer);
 
         if let Some(parent) = file_path.parent() {
             tokio::fs::create_dir_all(parent).await?
         }
 
-        match &self.filter {
-            VideoSearchOptions::VideoAudio => {
-                file_path = file_path.with_extension("mp4");
-                video.download(file_path).await?
+        let url = video.get_video_url();
+
+        // the size of the remote file is only looked up when an existing file may be kept
+        let remote_size = match self.overwrite_policy {
+            OverwritePolicy::SkipIfSameSize if tokio::fs::try_exists(&file_path).await? => {
+                self.remote_size(video).await?
             }
-            VideoSearchOptions::Video => {
-                file_path = file_path.with_extension("mp4");
-                video.download(file_path).await?
+            _ => None,
+        };
+
+        // the destination stays claimed until the file is written
+        let (file_path, claim) = match self
+            .overwrite_policy
+            .resolve(&file_path, remote_size)
+            .await?
+        {
+            Destination::Download(path, claim) => (path, claim),
+            Destination::Existing { path, size } => {
+                return Ok(self.skipped_file(path, &url, size));
             }
-            VideoSearchOptions::Audio => {
-                file_path = file_path.with_extension("mp3");
+        };
 
+        let (file_path, _claim, stream) = match &self.filter {
+            VideoSearchOptions::Audio => {
                 // `ffmpeg` must be installed on the computer to download a mp3 file
-
                 match video
-                    .download_with_ffmpeg(
-                        file_path.to_owned(),
-                        Some(FFmpegArgs {
-                            format: Some("mp3".to_string()),
-                            audio_filter: None,
-                            video_filter: None,
-                        }),
-                    )
+                    .stream_with_ffmpeg(Some(FFmpegArgs {
+                        format: Some("mp3".to_string()),
+                        audio_filter: None,
+                        video_filter: None,
+                    }))
                     .await
                 {
-                    Ok(v) => v,
+                    Ok(stream) => (file_path, claim, stream),
                     Err(_) => {
-                        // If download with ffmpeg fails, try downloading without ffmpeg
-                        file_path.with_extension("webm");
-                        video.download(file_path).await?
+                        // If streaming with ffmpeg fails, download the initial `webm` file without ffmpeg
+                        let stream = video.stream().await?;
+                        let total = Some(stream.content_length() as u64).filter(|len| *len > 0);
+
+                        let webm_path = path.with_extension(Some("webm"), &self.sanitizer);
+                        match self.overwrite_policy.resolve(&webm_path, total).await? {
+                            Destination::Download(path, claim) => (path, claim, stream),
+                            Destination::Existing { path, size } => {
+                                return Ok(self.skipped_file(path, &url, size));
+                            }
+                        }
                     }
                 }
             }
-            VideoSearchOptions::Custom(_) => video.download(file_path).await?,
+            _ => (file_path, claim, video.stream().await?),
+        };
+
+        let total = Some(stream.content_length() as u64).filter(|len| *len > 0);
+
+        // the video is written to a temporary file which is only renamed once complete
+        let mut part = PartFile::create(&file_path, &self.part_suffix).await?;
+
+        let tracker = ProgressTracker::start(self.progress.as_ref(), item, &url, 0, total);
+
+        while let Some(chunk) = stream.chunk().await? {
+            cancel::check(&self.cancellation)?;
+            part.file().write_all(&chunk).await?;
+            tracker.advance(chunk.len());
         }
 
-        Ok(())
+        let path = part.commit().await?;
+
+        Ok(DownloadedFile::new(
+            path,
+            &url,
+            tracker.downloaded(),
+            self.file_kind(),
+        ))
     }
 
     /// Downloads all videos from a playlist to the specified folder.
@@ -393,105 +660,485 @@ impl YoutubeDownloader {
     ///
     /// Returns a `DownloadError` if any error occurs during the download process, such as failure to create directories,
     /// fetching playlist information, or downloading the videos.
+    ///
+    /// Without output template, the videos are saved in a folder named after the playlist.
     async fn download_playlist_to<P: AsRef<Path>>(
         &self,
         folder_path: P,
-    ) -> Result<(), DownloadError> {
+    ) -> Result<DownloadReport, DownloadError> {
+        let playlist = cancel::or_cancelled(&self.cancellation, self.get_playlist()).await?;
+        let folder_path = folder_path.as_ref();
+        let path = &folder_path.join(self.sanitizer.sanitize(&playlist.name));
+        let playlist_name = &playlist.name;
+        let playlist_id = &self.playlist_id();
+        let filtered_videos = self.filter_videos(playlist.videos);
+
+        let results = futures::future::join_all(filtered_videos.into_iter().enumerate().map(
+            |(index, video_data)| async move {
+                let video = self.get_video_with_url_or_id(&video_data.video_id)?;
+
+                // the uploader and the upload date are only known from the video information
+                let info = match self.output_template.is_some() || self.info_json {
+                    true => self
+                        .cancellation
+                        .run_until_cancelled(video.get_basic_info())
+                        .await
+                        .and_then(Result::ok),
+                    false => None,
+                };
+
+                let video_path = match &self.output_template {
+                    Some(template) => {
+                        let mut fields = TemplateFields::new();
+                        fields
+                            .set("id", &video_data.video_id)
+                            .set_opt("title", video_data.get_title().ok())
+                            .set("duration", &video_data.length_seconds)
+                            .set("playlist", playlist_name)
+                            .set_opt("playlist_id", playlist_id.as_ref())
+                            .set("index", index + 1);
+
+                        if let Some(info) = &info {
+                            fields.extend(&Self::template_fields(info));
+                        }
+
+                        VideoPath::Templated {
+                            folder: folder_path,
+                            template,
+                            fields,
+                        }
+                    }
+                    None => {
+                        let title = match video_data.get_title() {
+                            Ok(title) => title,
+                            Err(_) => {
+                                let url = self.endpoints.watch_page(&video.get_video_id());
+                                cancel::or_cancelled(&self.cancellation, self.get_video_title(&url))
+                                    .await?
+                            }
+                        };
+                        VideoPath::Named {
+                            folder: path.to_owned(),
+                            name: title,
+                        }
+                    }
+                };
+
+                let media_duration = video_data
+                    .length_seconds
+                    .parse()
+                    .ok()
+                    .map(Duration::from_secs);
+
+                self.download_video_to_path(video, video_path, index, media_duration, info.as_ref())
+                    .await
+                    .map(DownloadReport::from)
+            },
+        ))
+        .await;
+
+        DownloadReport::from_results(results)
+    }
+
+    /// Downloads the video of the downloader to the file at `path`, which extension is set according to the downloaded format.
+    async fn download_single_video_to(
+        &self,
+        mut path: VideoPath<'_>,
+    ) -> Result<DownloadReport, DownloadError> {
+        let video = self.get_video()?;
+
+        // the duration and the template fields are only known if the video information can be retrieved
+        let info = self
+            .cancellation
+            .run_until_cancelled(video.get_basic_info())
+            .await
+            .and_then(Result::ok);
+
+        let media_duration = info
+            .as_ref()
+            .and_then(|info| info.video_details.length_seconds.parse().ok())
+            .map(Duration::from_secs);
+
+        if let (VideoPath::Templated { fields, .. }, Some(info)) = (&mut path, &info) {
+            fields.extend(&Self::template_fields(info));
+        }
+
+        let file = self
+            .download_video_to_path(video, path, 0, media_duration, info.as_ref())
+            .await?;
+
+        Ok(file.into())
+    }
+
+    /// Returns the ID of the playlist the URL points to, if any.
+    fn playlist_id(&self) -> Option<String> {
+        self.url
+            .query_pairs()
+            .find(|(key, _)| key == "list")
+            .map(|(_, id)| id.into_owned())
+    }
+
+    /// Keeps the videos of a playlist accepted by the playlist video filter, if any.
+    fn filter_videos(&self, videos: Vec<VideoData>) -> Vec<VideoData> {
+        match self.playlist_video_filter {
+            Some(filter) => videos.into_iter().filter(filter).collect(),
+            None => videos,
+        }
+    }
+
+    /// Retrieves the information of the playlist, its videos being described from the playlist page only, without their formats.
+    async fn playlist_info(&self) -> Result<MediaInfo, DownloadError> {
         let playlist = self.get_playlist().await?;
-        let path = &folder_path.as_ref().join(playlist.name);
 
-        let filtered_videos = match self.playlist_video_filter {
-            Some(filter) => playlist.videos.into_iter().filter(filter).collect(),
-            None => playlist.videos,
+        let entries = self
+            .filter_videos(playlist.videos)
+            .into_iter()
+            .map(|video| MediaInfo {
+                id: Some(video.video_id.to_owned()),
+                url: format!("https://www.youtube.com/watch?v={}", video.video_id),
+                title: video.get_title().ok(),
+                uploader: video
+                    .short_byline_text
+                    .runs
+                    .first()
+                    .map(|run| run.text.to_owned()),
+                duration: video.length_seconds.parse().ok(),
+                thumbnails: video
+                    .thumbnail
+                    .thumbnails
+                    .iter()
+                    .map(|thumbnail| Thumbnail {
+                        url: thumbnail.url.to_owned(),
+                        width: Some(thumbnail.width.into()),
+                        height: Some(thumbnail.height.into()),
+                    })
+                    .collect(),
+                ..Default::default()
+            })
+            .collect();
+
+        Ok(MediaInfo {
+            id: self.playlist_id(),
+            url: self.url.to_string(),
+            title: Some(playlist.name),
+            entries,
+            ..Default::default()
+        })
+    }
+
+    /// Returns the information of a video, listing all the formats it is available in.
+    fn media_info(info: &VideoInfo) -> MediaInfo {
+        let details = &info.video_details;
+        let uploader = match &details.author {
+            Some(author) => &author.name,
+            None => &details.owner_channel_name,
         };
 
-        let results =
-            futures::future::join_all(filtered_videos.into_iter().map(|video_data| async move {
-                let video = self.get_video_with_url_or_id(&video_data.video_id)?;
+        let formats = info.formats.iter().map(format_info).collect();
+
+        MediaInfo {
+            id: Some(details.video_id.to_owned()),
+            url: details.video_url.to_owned(),
+            title: Some(details.title.to_owned()),
+            uploader: Some(uploader.to_owned()),
+            upload_date: compact_date(&details.upload_date),
+            duration: details.length_seconds.parse().ok(),
+            description: Some(details.description.to_owned()).filter(|d| !d.is_empty()),
+            thumbnails: details
+                .thumbnails
+                .iter()
+                .map(|thumbnail| Thumbnail {
+                    url: thumbnail.url.to_owned(),
+                    width: Some(thumbnail.width),
+                    height: Some(thumbnail.height),
+                })
+                .collect(),
+            media: vec![Media {
+                kind: FileKind::Video,
+                formats,
+            }],
+            entries: Vec::new(),
+        }
+    }
 
-                let title = match video_data.get_title() {
-                    Ok(title) => title,
-                    Err(_) => Self::get_video_title(video.get_video_url().as_str()).await?,
-                };
+    /// Returns the format of the video picked by the filter of the downloader, the one with the highest resolution and bitrate.
+    fn chosen_format(&self, info: &VideoInfo) -> Option<MediaFormat> {
+        info.formats
+            .iter()
+            .filter(|format| match &self.filter {
+                VideoSearchOptions::VideoAudio => format.has_video && format.has_audio,
+                VideoSearchOptions::Video => format.has_video && !format.has_audio,
+                VideoSearchOptions::Audio => format.has_audio && !format.has_video,
+                VideoSearchOptions::Custom(filter) => filter(format),
+            })
+            .max_by_key(|format| (format.height.unwrap_or_default(), format.bitrate))
+            .map(format_info)
+    }
 
-                let download_result = self
-                    .download_video_to_path(video, path.join(&Self::sanitize_file_name(&title)))
-                    .await;
+    /// Returns the sidecar of the downloaded video, holding its `VideoDetails` if its information is known.
+    fn info_json(
+        &self,
+        video: &Video,
+        info: Option<&VideoInfo>,
+        file: &DownloadedFile,
+    ) -> InfoJson {
+        let info_json = InfoJson {
+            url: video.get_video_url(),
+            extractor: EXTRACTOR.to_owned(),
+            id: Some(video.get_video_id()),
+            ..Default::default()
+        };
 
-                if self.print_download_status {
-                    if let Err(err) = &download_result {
-                        eprintln!("Error downloading video named `{}`: {:?}", title, err);
-                    } else {
-                        println!("Video downloaded successfully: {}", title);
-                    }
-                }
+        let Some(info) = info else {
+            return info_json;
+        };
 
-                download_result
-            }))
-            .await;
+        let details = &info.video_details;
+        let uploader = match &details.author {
+            Some(author) => &author.name,
+            None => &details.owner_channel_name,
+        };
 
-        for result in results {
-            result?
+        // the audio may have been converted, the extension is the one of the written file
+        let format = self.chosen_format(info).map(|format| MediaFormat {
+            ext: file
+                .path
+                .extension()
+                .map(|ext| ext.to_string_lossy().into_owned()),
+            ..format
+        });
+
+        InfoJson {
+            url: details.video_url.to_owned(),
+            id: Some(details.video_id.to_owned()),
+            title: Some(details.title.to_owned()),
+            uploader: Some(uploader.to_owned()),
+            upload_date: compact_date(&details.upload_date),
+            format,
+            details: serde_json::to_value(details).unwrap_or_default(),
+            ..info_json
         }
-
-        Ok(())
     }
-}
 
-/// Simplified representation of a youtube playlist.
-#[derive(Debug, Clone)]
-pub struct Playlist {
-    pub name: String,
-    pub videos: Vec<VideoData>,
-}
+    /// Returns the fields of a video available to output templates.
+    fn template_fields(info: &VideoInfo) -> TemplateFields {
+        let details = &info.video_details;
+        let uploader = match &details.author {
+            Some(author) => &author.name,
+            None => &details.owner_channel_name,
+        };
 
-#[async_trait::async_trait]
-impl Downloader for YoutubeDownloader {
-    async fn download_to<P: AsRef<Path> + std::marker::Send>(
+        let mut fields = TemplateFields::new();
+        fields
+            .set("id", &details.video_id)
+            .set("title", &details.title)
+            .set("url", &details.video_url)
+            .set("uploader", uploader)
+            .set("channel_id", &details.channel_id)
+            .set_opt("upload_date", compact_date(&details.upload_date))
+            .set("duration", &details.length_seconds);
+
+        fields
+    }
+
+    /// Downloads the video, or the videos of the playlist, to the folder at `folder_path`.
+    async fn download_to_folder(
         &self,
-        folder_path: P,
-    ) -> Result<(), DownloadError> {
-        if self.print_download_status {
-            println!("Downloading...");
+        folder_path: &Path,
+    ) -> Result<DownloadReport, DownloadError> {
+        trace::started();
+
+        if let Some(template) = &self.output_template {
+            if self.is_playlist {
+                return self.download_playlist_to(folder_path).await;
+            }
+
+            let path = VideoPath::Templated {
+                folder: folder_path,
+                template,
+                fields: TemplateFields::new(),
+            };
+
+            return self.download_single_video_to(path).await;
         }
 
         let name = match self.video_name.to_owned() {
             Some(value) => value,
-            None => Self::get_video_title(self.url.as_str()).await?,
+            None => {
+                cancel::or_cancelled(
+                    &self.cancellation,
+                    self.get_video_title(&self.endpoints.page(&self.url)),
+                )
+                .await?
+            }
         };
 
-        let path = folder_path.as_ref().join(Self::sanitize_file_name(&name));
+        if self.is_playlist {
+            let path = folder_path.join(self.sanitizer.sanitize(&name));
+
+            return self.download_playlist_to(path).await;
+        }
+
+        self.download_single_video_to(VideoPath::Named {
+            folder: folder_path.to_owned(),
+            name,
+        })
+        .await
+    }
+
+    /// Downloads the video, or the videos of the playlist, to the current directory.
+    async fn download_to_current_dir(&self) -> Result<DownloadReport, DownloadError> {
+        if self.output_template.is_some() {
+            return self.download_to_folder(Path::new("./")).await;
+        }
+
+        trace::started();
 
         if self.is_playlist {
-            self.download_playlist_to(path).await?;
+            return self.download_playlist_to("./").await;
+        }
+
+        let name = match self.video_name.to_owned() {
+            Some(value) => value,
+            None => {
+                cancel::or_cancelled(
+                    &self.cancellation,
+                    self.get_video_title(&self.endpoints.page(&self.url)),
+                )
+                .await?
+            }
+        };
 
-            return Ok(());
+        self.download_single_video_to(VideoPath::Named {
+            folder: PathBuf::from("./"),
+            name,
+        })
+        .await
+    }
+
+    /// Retrieves the information of the video or of the playlist, see [`Downloader::info`].
+    async fn video_info(&self) -> Result<MediaInfo, DownloadError> {
+        if self.is_playlist {
+            return cancel::or_cancelled(&self.cancellation, self.playlist_info()).await;
         }
 
         let video = self.get_video()?;
-        self.download_video_to_path(video, path).await?;
+        let info =
+            cancel::or_cancelled(&self.cancellation, async { Ok(video.get_info().await?) }).await?;
 
-        Ok(())
+        Ok(Self::media_info(&info))
     }
+}
 
-    async fn download(&self) -> Result<(), DownloadError> {
-        if self.is_playlist {
-            self.download_playlist_to("./").await?;
+/// The path a video is saved to, which extension depends on the downloaded format.
+enum VideoPath<'a> {
+    /// The folder of the file and its name, without extension and not yet sanitized.
+    Named { folder: PathBuf, name: String },
+    /// The output template rendered with the fields of the video, relative to the download folder.
+    Templated {
+        folder: &'a Path,
+        template: &'a OutputTemplate,
+        fields: TemplateFields,
+    },
+}
 
-            return Ok(());
+impl VideoPath<'_> {
+    /// Returns the path of the file with the given extension, if known, its name being sanitized.
+    fn with_extension(&self, extension: Option<&str>, sanitizer: &FileNameSanitizer) -> PathBuf {
+        match self {
+            VideoPath::Named { folder, name } => {
+                // the extension is added before sanitizing so that it is kept if the name is truncated
+                let name = match extension {
+                    Some(extension) => format!("{}.{}", name, extension),
+                    None => name.to_owned(),
+                };
+
+                folder.join(sanitizer.sanitize(&name))
+            }
+            VideoPath::Templated {
+                folder,
+                template,
+                fields,
+            } => {
+                let mut fields = fields.clone();
+                fields.set_opt("ext", extension);
+
+                folder.join(template.render(&fields, sanitizer))
+            }
         }
+    }
+}
 
-        let video = self.get_video()?;
+/// Returns the information of a format a video is available in.
+fn format_info(format: &VideoFormat) -> MediaFormat {
+    let (kind, mime) = match format.has_video {
+        true => (FileKind::Video, "video"),
+        false => (FileKind::Audio, "audio"),
+    };
+
+    MediaFormat {
+        format_id: Some(format.itag.to_string()),
+        url: format.url.to_owned(),
+        kind,
+        ext: Some(format.mime_type.container.to_owned()),
+        mime_type: Some(format!(
+            "{}/{}; codecs=\"{}\"",
+            mime,
+            format.mime_type.container,
+            format.mime_type.codecs.join(", ")
+        )),
+        width: format.width,
+        height: format.height,
+        bitrate: Some(format.bitrate),
+        size: format
+            .content_length
+            .as_deref()
+            .and_then(|length| length.parse().ok()),
+    }
+}
 
-        let name = match self.video_name.to_owned() {
-            Some(value) => value,
-            None => Self::get_video_title(self.url.as_str()).await?,
-        };
+/// Converts a date such as `2024-04-10` or `2024-04-10T05:00:00-07:00` to `YYYYMMDD`.
+fn compact_date(date: &str) -> Option<String> {
+    let digits: String = date
+        .chars()
+        .take(10)
+        .filter(|c| c.is_ascii_digit())
+        .collect();
+
+    (digits.len() == 8).then_some(digits)
+}
+
+/// Simplified representation of a youtube playlist.
+#[derive(Debug, Clone)]
+pub struct Playlist {
+    pub name: String,
+    pub videos: Vec<VideoData>,
+}
+
+#[async_trait::async_trait]
+impl Downloader for YoutubeDownloader {
+    async fn download_to<P: AsRef<Path> + std::marker::Send>(
+        &self,
+        folder_path: P,
+    ) -> Result<DownloadReport, DownloadError> {
+        self.download_to_folder(folder_path.as_ref())
+            .instrument(trace::download(EXTRACTOR, self.url.as_str()))
+            .await
+            .map_err(|err| err.with_context(EXTRACTOR, self.url.as_str()))
+    }
 
-        let title = Self::sanitize_file_name(&name);
+    async fn download(&self) -> Result<DownloadReport, DownloadError> {
+        self.download_to_current_dir()
+            .instrument(trace::download(EXTRACTOR, self.url.as_str()))
+            .await
+            .map_err(|err| err.with_context(EXTRACTOR, self.url.as_str()))
+    }
 
-        self.download_video_to_path(video, Path::new("./").join(&title))
+    /// Retrieves the information of the video, or of the videos of the playlist if the URL points to one.
+    async fn info(&self) -> Result<MediaInfo, DownloadError> {
+        self.video_info()
             .await
+            .map_err(|err| err.with_context(EXTRACTOR, self.url.as_str()))
     }
 
     fn is_valid_url(url: &Url) -> bool {
@@ -501,7 +1148,47 @@ impl Downloader for YoutubeDownloader {
             || url.domain() == Some("www.youtu.be")
     }
 
-    fn get_dl_status(&mut self) -> &mut bool {
-        &mut self.print_download_status
+    fn get_part_suffix(&mut self) -> &mut String {
+        &mut self.part_suffix
+    }
+
+    fn get_retry_policy(&mut self) -> &mut RetryPolicy {
+        &mut self.retry_policy
+    }
+
+    fn get_client(&mut self) -> &mut Client {
+        &mut self.client
+    }
+
+    fn get_http_config(&mut self) -> &mut HttpConfig {
+        &mut self.http_config
+    }
+
+    fn get_transport(&mut self) -> &mut Option<Transport> {
+        &mut self.transport
+    }
+
+    fn get_progress_handler(&mut self) -> &mut Option<ProgressHandler> {
+        &mut self.progress
+    }
+
+    fn get_output_template(&mut self) -> &mut Option<OutputTemplate> {
+        &mut self.output_template
+    }
+
+    fn get_file_name_sanitizer(&mut self) -> &mut FileNameSanitizer {
+        &mut self.sanitizer
+    }
+
+    fn get_overwrite_policy(&mut self) -> &mut OverwritePolicy {
+        &mut self.overwrite_policy
+    }
+
+    fn get_cancellation_token(&mut self) -> &mut CancellationToken {
+        &mut self.cancellation
+    }
+
+    fn get_info_json(&mut self) -> &mut bool {
+        &mut self.info_json
     }
 }
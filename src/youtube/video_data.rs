@@ -1,4 +1,4 @@
-use crate::prelude::DownloadError;
+use crate::prelude::{DownloadError, ErrorKind};
 
 use self::{
     index::VideoIndex,
@@ -64,10 +64,13 @@ impl VideoData {
             .runs
             .get(0)
             .ok_or_else(|| {
-                DownloadError::YoutubeError(format!(
-                    "Could not retrieve title of video with id `{}`",
-                    self.video_id
-                ))
+                DownloadError::new(
+                    ErrorKind::Extraction,
+                    format!(
+                        "Could not retrieve title of video with id `{}`",
+                        self.video_id
+                    ),
+                )
             })?
             .text
             .to_owned())
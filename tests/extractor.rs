@@ -0,0 +1,103 @@
+use rusty_dl::extractor::ResourceExtractor;
+use rusty_dl::prelude::*;
+use url::Url;
+
+/// Extractor of the links of a single host, downloading them as resources.
+struct HostExtractor {
+    host: &'static str,
+    priority: i32,
+}
+
+impl Extractor for HostExtractor {
+    fn name(&self) -> &str {
+        self.host
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        url.host_str() == Some(self.host)
+    }
+
+    fn create(&self, url: &Url) -> Result<Box<dyn DynDownloader>, DownloadError> {
+        let mut downloader = ResourceDownloader::new(url.as_str())?;
+        downloader.with_name(self.host.to_owned());
+
+        Ok(downloader.boxed())
+    }
+}
+
+#[test]
+fn registry_picks_builtin_extractors() -> Result<(), DownloadError> {
+    let registry = ExtractorRegistry::new();
+
+    let downloader = registry.downloader("https://www.youtube.com/watch?v=dQw4w9WgXcQ")?;
+    assert!(downloader.downcast_ref::<YoutubeDownloader>().is_some());
+
+    let downloader = registry.downloader("https://x.com/user/status/1234567890")?;
+    assert!(downloader.downcast_ref::<TwitterDownloader>().is_some());
+
+    let downloader = registry.downloader("https://example.com/file.txt")?;
+    assert!(downloader.downcast_ref::<ResourceDownloader>().is_some());
+    assert!(downloader.downcast_ref::<YoutubeDownloader>().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn registry_orders_extractors_by_priority() -> Result<(), DownloadError> {
+    let mut registry = ExtractorRegistry::new();
+    registry
+        .register(HostExtractor {
+            host: "example.com",
+            priority: -1,
+        })
+        .register(HostExtractor {
+            host: "www.youtube.com",
+            priority: 10,
+        });
+
+    let names: Vec<&str> = registry.extractors().map(|e| e.name()).collect();
+    assert_eq!(
+        names,
+        ["www.youtube.com", "youtube", "twitter", "example.com"]
+    );
+
+    let url = Url::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ").unwrap();
+    assert_eq!(
+        registry.find(&url).map(|e| e.name()),
+        Some("www.youtube.com")
+    );
+
+    let downloader = registry.downloader(url.as_str())?;
+    assert!(downloader.downcast_ref::<ResourceDownloader>().is_some());
+
+    let url = Url::parse("https://example.com/file.txt").unwrap();
+    assert_eq!(registry.find(&url).map(|e| e.name()), Some("example.com"));
+
+    Ok(())
+}
+
+#[test]
+fn registry_without_fallback_rejects_unknown_links() {
+    let mut registry = ExtractorRegistry::empty();
+    registry.register(HostExtractor {
+        host: "example.com",
+        priority: 0,
+    });
+
+    assert!(registry.downloader("https://example.com/file.txt").is_ok());
+    assert!(matches!(
+        registry.downloader("https://example.org/file.txt"),
+        Err(DownloadError::InvalidUrl(_))
+    ));
+    assert!(matches!(
+        registry.downloader("not a link"),
+        Err(DownloadError::InvalidUrl(_))
+    ));
+
+    registry.with_fallback(ResourceExtractor);
+    assert!(registry.downloader("https://example.org/file.txt").is_ok());
+}
@@ -0,0 +1,1001 @@
+use rusty_dl::prelude::*;
+use std::collections::HashMap;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Minimal representation of a request received by the local test server.
+#[allow(dead_code)]
+struct TestRequest {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+}
+
+/// Minimal representation of a response sent by the local test server.
+struct TestResponse {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+    /// When `true` the body is sent with `Transfer-Encoding: chunked` instead of a `Content-Length`.
+    chunked: bool,
+    /// Overrides the advertised `Content-Length`, to simulate a connection dropped mid-transfer.
+    length: Option<usize>,
+}
+
+impl TestResponse {
+    fn ok(body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            body,
+            chunked: false,
+            length: None,
+        }
+    }
+}
+
+/// Spawns a local HTTP server answering every request with `handler` and returns its base URL.
+async fn serve(handler: fn(&TestRequest) -> TestResponse) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(_) => return,
+            };
+
+            tokio::spawn(async move {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 1024];
+
+                while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut chunk).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                    }
+                }
+
+                let head = String::from_utf8_lossy(&buf).to_string();
+                let mut lines = head.lines();
+                let mut request_line = lines.next().unwrap_or_default().split(' ');
+
+                let request = TestRequest {
+                    method: request_line.next().unwrap_or_default().to_owned(),
+                    path: request_line.next().unwrap_or_default().to_owned(),
+                    headers: lines
+                        .take_while(|line| !line.is_empty())
+                        .filter_map(|line| line.split_once(':'))
+                        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_owned()))
+                        .collect(),
+                };
+
+                let response = handler(&request);
+
+                let mut out = format!("HTTP/1.1 {} TEST\r\nConnection: close\r\n", response.status);
+                for (key, value) in &response.headers {
+                    out.push_str(&format!("{}: {}\r\n", key, value));
+                }
+
+                if response.chunked {
+                    out.push_str("Transfer-Encoding: chunked\r\n\r\n");
+                    let _ = socket.write_all(out.as_bytes()).await;
+
+                    if request.method != "HEAD" {
+                        for part in response.body.chunks(1000) {
+                            let _ = socket
+                                .write_all(format!("{:x}\r\n", part.len()).as_bytes())
+                                .await;
+                            let _ = socket.write_all(part).await;
+                            let _ = socket.write_all(b"\r\n").await;
+                        }
+                        let _ = socket.write_all(b"0\r\n\r\n").await;
+                    }
+                } else {
+                    let length = response.length.unwrap_or(response.body.len());
+                    out.push_str(&format!("Content-Length: {}\r\n\r\n", length));
+                    let _ = socket.write_all(out.as_bytes()).await;
+
+                    if request.method != "HEAD" {
+                        let _ = socket.write_all(&response.body).await;
+                    }
+                }
+
+                let _ = socket.shutdown().await;
+            });
+        }
+    });
+
+    format!("http://{}", addr)
+}
+
+/// Deterministic content used as the served resource.
+fn payload() -> Vec<u8> {
+    (0..200_000u32).map(|i| (i % 251) as u8).collect()
+}
+
+fn test_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::path::Path::new("./tests-run/resource/").join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[tokio::test]
+async fn resource_streams_body_to_disk() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse::ok(payload())).await;
+    let dir = test_dir("stream");
+
+    let downloader = ResourceDownloader::new(&format!("{}/file.bin", base))?;
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("file.bin"))?, payload());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_without_content_length() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse {
+        chunked: true,
+        ..TestResponse::ok(payload())
+    })
+    .await;
+    let dir = test_dir("chunked");
+
+    let downloader = ResourceDownloader::new(&format!("{}/chunked.bin", base))?;
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("chunked.bin"))?, payload());
+
+    Ok(())
+}
+
+/// Serves `payload()` with an `ETag`, honouring `Range` and `If-Range` headers.
+fn ranged_payload(request: &TestRequest) -> TestResponse {
+    let body = payload();
+    let etag = "\"v1\"".to_owned();
+
+    let range = request
+        .headers
+        .get("range")
+        .and_then(|range| range.strip_prefix("bytes="))
+        .and_then(|range| range.split_once('-'));
+    let range_start = range.and_then(|(start, _)| start.parse::<usize>().ok());
+    let range_end = range
+        .and_then(|(_, end)| end.parse::<usize>().ok())
+        .map_or(body.len() - 1, |end| end.min(body.len() - 1));
+    let validator_matches = request
+        .headers
+        .get("if-range")
+        .is_none_or(|value| *value == etag);
+
+    match range_start {
+        Some(start) if validator_matches && start < body.len() => TestResponse {
+            status: 206,
+            headers: vec![
+                ("ETag", etag),
+                ("Accept-Ranges", "bytes".to_owned()),
+                (
+                    "Content-Range",
+                    format!("bytes {}-{}/{}", start, range_end, body.len()),
+                ),
+            ],
+            body: body[start..=range_end].to_vec(),
+            chunked: false,
+            length: None,
+        },
+        _ => TestResponse {
+            headers: vec![("ETag", etag), ("Accept-Ranges", "bytes".to_owned())],
+            // the whole content is only served where a full download is expected, so that
+            // the tests can tell when a range should have been used instead
+            ..TestResponse::ok(match request.path.as_str() {
+                "/partial.bin" | "/segmented.bin" => vec![0; body.len()],
+                _ => body,
+            })
+        },
+    }
+}
+
+#[tokio::test]
+async fn resource_resumes_partial_file() -> Result<(), DownloadError> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static WHOLE_GETS: AtomicUsize = AtomicUsize::new(0);
+
+    let base = serve(|request| {
+        if request.method == "GET" && !request.headers.contains_key("range") {
+            WHOLE_GETS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        ranged_payload(request)
+    })
+    .await;
+    let dir = test_dir("resume");
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("partial.bin.part"), &payload()[..50_000])?;
+    std::fs::write(dir.join("partial.bin.part.resume"), "\"v1\"")?;
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/partial.bin", base))?;
+    downloader.resume();
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("partial.bin"))?, payload());
+    assert!(!dir.join("partial.bin.part").exists());
+    assert!(!dir.join("partial.bin.part.resume").exists());
+    // the file is named from the headers of a HEAD request, only the missing bytes are downloaded
+    assert_eq!(WHOLE_GETS.load(Ordering::SeqCst), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_restarts_when_validator_changed() -> Result<(), DownloadError> {
+    let base = serve(ranged_payload).await;
+    let dir = test_dir("resume_changed");
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("changed.bin.part"), vec![0u8; 50_000])?;
+    std::fs::write(dir.join("changed.bin.part.resume"), "\"v0\"")?;
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/changed.bin", base))?;
+    downloader.resume();
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("changed.bin"))?, payload());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_restart_downloads_the_whole_response() -> Result<(), DownloadError> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static GETS: AtomicUsize = AtomicUsize::new(0);
+
+    let base = serve(|request| {
+        GETS.fetch_add(1, Ordering::SeqCst);
+        ranged_payload(request)
+    })
+    .await;
+    let dir = test_dir("resume_whole");
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("whole.bin.part"), vec![0u8; 50_000])?;
+    std::fs::write(dir.join("whole.bin.part.resume"), "\"v0\"")?;
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/whole.bin", base))?;
+    downloader.resume();
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("whole.bin"))?, payload());
+    // the HEAD request naming the file, then the one sending the whole resource as it changed
+    assert_eq!(GETS.load(Ordering::SeqCst), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_segmented_download() -> Result<(), DownloadError> {
+    let base = serve(ranged_payload).await;
+    let dir = test_dir("segmented");
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/segmented.bin", base))?;
+    downloader.segmented(4).with_min_segment_size(10_000);
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("segmented.bin"))?, payload());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_segmented_download_only_probes_the_headers() -> Result<(), DownloadError> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static WHOLE_GETS: AtomicUsize = AtomicUsize::new(0);
+
+    let base = serve(|request| {
+        if request.method == "GET" && !request.headers.contains_key("range") {
+            WHOLE_GETS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        ranged_payload(request)
+    })
+    .await;
+    let dir = test_dir("segmented_probe");
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/segmented.bin", base))?;
+    downloader.segmented(4).with_min_segment_size(10_000);
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("segmented.bin"))?, payload());
+    assert_eq!(WHOLE_GETS.load(Ordering::SeqCst), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_segmented_falls_back_without_ranges() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse::ok(payload())).await;
+    let dir = test_dir("segmented_fallback");
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/whole.bin", base))?;
+    downloader.segmented(4).with_min_segment_size(10_000);
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("whole.bin"))?, payload());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_name_from_content_disposition() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse {
+        headers: vec![(
+            "Content-Disposition",
+            "attachment; filename=\"fallback.txt\"; filename*=UTF-8''na%C3%AFve%20report.txt"
+                .to_owned(),
+        )],
+        ..TestResponse::ok(payload())
+    })
+    .await;
+    let dir = test_dir("content_disposition");
+
+    let downloader = ResourceDownloader::new(&format!("{}/download?id=42", base))?;
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("naïve report.txt"))?, payload());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_extension_from_content_type() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse {
+        headers: vec![("Content-Type", "image/png".to_owned())],
+        ..TestResponse::ok(payload())
+    })
+    .await;
+    let dir = test_dir("content_type");
+
+    let downloader = ResourceDownloader::new(&format!("{}/download?id=42", base))?;
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("download.png"))?, payload());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_rejects_error_status() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse {
+        status: 404,
+        ..TestResponse::ok(b"<html>Not Found</html>".to_vec())
+    })
+    .await;
+    let dir = test_dir("not_found");
+
+    let downloader = ResourceDownloader::new(&format!("{}/missing.bin", base))?;
+    let result = downloader.download_to(&dir).await;
+
+    let err = result.as_ref().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert!(!err.is_retryable());
+    assert_eq!(err.extractor(), Some("resource"));
+    assert_eq!(err.url(), Some(format!("{}/missing.bin", base).as_str()));
+
+    match result.map_err(DownloadError::into_inner) {
+        Err(DownloadError::HttpStatus { status, url, body }) => {
+            assert_eq!(status.as_u16(), 404);
+            assert!(url.ends_with("/missing.bin"));
+            assert_eq!(body, "<html>Not Found</html>");
+        }
+        other => panic!("expected an HTTP status error, got {:?}", other),
+    }
+    assert!(!dir.join("missing.bin").exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_writes_through_part_file() -> Result<(), DownloadError> {
+    let base = serve(|request| match request.path.as_str() {
+        "/aborted.bin" => TestResponse {
+            length: Some(payload().len() * 2),
+            ..TestResponse::ok(payload())
+        },
+        _ => TestResponse::ok(payload()),
+    })
+    .await;
+    let dir = test_dir("part_file");
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/complete.bin", base))?;
+    downloader.with_part_suffix(".tmp");
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("complete.bin"))?, payload());
+    assert!(!dir.join("complete.bin.tmp").exists());
+
+    let downloader = ResourceDownloader::new(&format!("{}/aborted.bin", base))?;
+    assert!(downloader.download_to(&dir).await.is_err());
+
+    assert!(!dir.join("aborted.bin").exists());
+    assert!(!dir.join("aborted.bin.part").exists());
+
+    Ok(())
+}
+
+/// SHA-256 digest of `payload()`.
+const PAYLOAD_SHA256: &str = "e24bc62381f1224fbbb74688663f8f9743b9680b193edd666835e97b06e730eb";
+
+#[tokio::test]
+async fn resource_verifies_checksum() -> Result<(), DownloadError> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static RANGES: AtomicUsize = AtomicUsize::new(0);
+
+    let base = serve(|request| {
+        if request.headers.contains_key("range") {
+            RANGES.fetch_add(1, Ordering::SeqCst);
+        }
+
+        ranged_payload(request)
+    })
+    .await;
+    let dir = test_dir("checksum");
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/verified.bin", base))?;
+    downloader.with_checksum(Checksum::Sha256(PAYLOAD_SHA256.to_uppercase()));
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("verified.bin"))?, payload());
+
+    // the digest is computed while the data arrives, so the resource is not split into segments
+    let mut downloader = ResourceDownloader::new(&format!("{}/unsegmented.bin", base))?;
+    downloader
+        .segmented(4)
+        .with_min_segment_size(10_000)
+        .with_checksum(Checksum::Md5("415d6e662118c229c6ad3f950c24702a".to_owned()));
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("unsegmented.bin"))?, payload());
+    assert_eq!(RANGES.load(Ordering::SeqCst), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_deletes_file_on_checksum_mismatch() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse::ok(payload())).await;
+    let dir = test_dir("checksum_mismatch");
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/corrupted.bin", base))?;
+    downloader.with_checksum(Checksum::Blake3("00".repeat(32)));
+
+    match downloader
+        .download_to(&dir)
+        .await
+        .map_err(DownloadError::into_inner)
+    {
+        Err(DownloadError::ChecksumMismatch {
+            algorithm,
+            expected,
+            actual,
+            ..
+        }) => {
+            assert_eq!(algorithm, "BLAKE3");
+            assert_eq!(expected, "00".repeat(32));
+            assert_eq!(actual.len(), 64);
+        }
+        other => panic!("expected a checksum mismatch, got {:?}", other),
+    }
+
+    assert!(!dir.join("corrupted.bin").exists());
+    assert!(!dir.join("corrupted.bin.part").exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_retries_transient_failures() -> Result<(), DownloadError> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    static UNAVAILABLE: AtomicUsize = AtomicUsize::new(0);
+    static TRUNCATED: AtomicUsize = AtomicUsize::new(0);
+
+    let base = serve(|request| match request.path.as_str() {
+        "/unavailable.bin" if UNAVAILABLE.fetch_add(1, Ordering::SeqCst) < 2 => TestResponse {
+            status: 503,
+            headers: vec![("Retry-After", "0".to_owned())],
+            ..TestResponse::ok(Vec::new())
+        },
+        "/truncated.bin" if TRUNCATED.fetch_add(1, Ordering::SeqCst) < 1 => TestResponse {
+            length: Some(payload().len() * 2),
+            ..TestResponse::ok(payload())
+        },
+        "/never.bin" => TestResponse {
+            status: 503,
+            ..TestResponse::ok(Vec::new())
+        },
+        _ => TestResponse::ok(payload()),
+    })
+    .await;
+    let dir = test_dir("retry");
+    let policy = RetryPolicy::new().base_delay(Duration::from_millis(10));
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/unavailable.bin", base))?;
+    downloader.with_retry_policy(policy.clone());
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("unavailable.bin"))?, payload());
+    assert_eq!(UNAVAILABLE.load(Ordering::SeqCst), 3);
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/truncated.bin", base))?;
+    downloader.with_retry_policy(policy);
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("truncated.bin"))?, payload());
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/never.bin", base))?;
+    downloader.with_retry_policy(RetryPolicy::never());
+
+    match downloader
+        .download_to(&dir)
+        .await
+        .map_err(DownloadError::into_inner)
+    {
+        Err(err @ DownloadError::HttpStatus { .. }) => {
+            assert_eq!(
+                err.kind(),
+                ErrorKind::HttpStatus(reqwest::StatusCode::SERVICE_UNAVAILABLE)
+            );
+            assert!(err.is_retryable());
+        }
+        other => panic!("expected an HTTP status error, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_uses_provided_client() -> Result<(), DownloadError> {
+    let base = serve(|request| match request.headers.get("x-client") {
+        Some(value) if value == "shared" => TestResponse::ok(payload()),
+        _ => TestResponse {
+            status: 403,
+            ..TestResponse::ok(Vec::new())
+        },
+    })
+    .await;
+    let dir = test_dir("client");
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-client", "shared".parse().unwrap());
+    let client = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()?;
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/client.bin", base))?;
+    downloader.with_client(client);
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("client.bin"))?, payload());
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/config.bin", base))?;
+    downloader.with_http_config(&HttpConfig::new().timeout(std::time::Duration::from_secs(5)))?;
+
+    assert!(downloader.download_to(&dir).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_sends_requests_through_proxy() -> Result<(), DownloadError> {
+    // `dXNlcjpwYXNzd29yZA==` is `user:password` encoded in base64
+    let proxy = serve(|request| {
+        let authorized = request.headers.get("proxy-authorization")
+            == Some(&"Basic dXNlcjpwYXNzd29yZA==".to_owned());
+
+        match request.path.as_str() {
+            "http://media.example/proxied.bin" if authorized => TestResponse::ok(payload()),
+            _ => TestResponse {
+                status: 407,
+                ..TestResponse::ok(Vec::new())
+            },
+        }
+    })
+    .await;
+    let dir = test_dir("proxy");
+
+    let mut downloader = ResourceDownloader::new("http://media.example/proxied.bin")?;
+    downloader.with_proxy(ProxyConfig::new(&proxy).basic_auth("user", "password"))?;
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("proxied.bin"))?, payload());
+
+    let mut downloader = ResourceDownloader::new("http://media.example/proxied.bin")?;
+    assert!(matches!(
+        downloader.with_proxy(ProxyConfig::new("ftp://proxy.example:21")),
+        Err(DownloadError::InvalidUrl(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_reports_progress() -> Result<(), DownloadError> {
+    let base = serve(|request| match request.path.as_str() {
+        "/progress.bin" => TestResponse::ok(payload()),
+        _ => TestResponse {
+            status: 404,
+            ..TestResponse::ok(Vec::new())
+        },
+    })
+    .await;
+    let dir = test_dir("progress");
+    let total = payload().len() as u64;
+
+    let (handler, mut events) = ProgressHandler::channel();
+    let mut downloader = ResourceDownloader::new(&format!("{}/progress.bin", base))?;
+    downloader.with_progress(handler.clone());
+    downloader.download_to(&dir).await?;
+
+    match events.recv().await {
+        Some(ProgressEvent::Started {
+            item: 0, total: t, ..
+        }) => assert_eq!(t, Some(total)),
+        other => panic!("expected a started event, got {:?}", other),
+    }
+
+    let mut last_downloaded = 0;
+    loop {
+        match events.recv().await {
+            Some(ProgressEvent::Progress { downloaded, .. }) => {
+                assert!(downloaded > last_downloaded);
+                last_downloaded = downloaded;
+            }
+            Some(ProgressEvent::Finished {
+                path, downloaded, ..
+            }) => {
+                assert_eq!(path, dir.join("progress.bin"));
+                assert_eq!(downloaded, total);
+                break;
+            }
+            other => panic!("expected a progress event, got {:?}", other),
+        }
+    }
+    assert_eq!(last_downloaded, total);
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/missing.bin", base))?;
+    downloader.with_progress(handler);
+    assert!(downloader.download_to(&dir).await.is_err());
+
+    assert!(matches!(
+        events.recv().await,
+        Some(ProgressEvent::Failed { item: 0, .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[allow(deprecated)]
+fn print_dl_status_does_not_install_a_progress_handler() -> Result<(), DownloadError> {
+    let mut downloader = ResourceDownloader::new("https://example.com/file.zip")?;
+    downloader.print_dl_status();
+
+    assert!(downloader.get_progress_handler().is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_returns_report() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse {
+        headers: vec![("Content-Type", "video/mp4".to_owned())],
+        ..TestResponse::ok(payload())
+    })
+    .await;
+    let dir = test_dir("report");
+    let url = format!("{}/clip.mp4", base);
+
+    let downloader = ResourceDownloader::new(&url)?;
+    let report = downloader.download_to(&dir).await?;
+
+    assert_eq!(report.len(), 1);
+    assert_eq!(report.total_size(), payload().len() as u64);
+
+    let file = &report.files()[0];
+    assert_eq!(file.path, dir.join("clip.mp4"));
+    assert_eq!(file.url, url);
+    assert_eq!(file.size, payload().len() as u64);
+    assert_eq!(file.kind, FileKind::Video);
+    assert_eq!(file.media_duration, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_cancellation_removes_part_file() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse::ok(payload())).await;
+    let dir = test_dir("cancel");
+
+    let token = CancellationToken::new();
+    let cancel_on_start = token.clone();
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/cancelled.bin", base))?;
+    downloader.with_cancellation(token);
+    downloader.on_progress(move |event| {
+        if let ProgressEvent::Started { .. } = event {
+            cancel_on_start.cancel();
+        }
+    });
+
+    match downloader
+        .download_to(&dir)
+        .await
+        .map_err(DownloadError::into_inner)
+    {
+        Err(DownloadError::Cancelled { completed }) => assert!(completed.is_empty()),
+        other => panic!("expected the download to be cancelled, got {:?}", other),
+    }
+
+    assert!(!dir.join("cancelled.bin").exists());
+    assert!(!dir.join("cancelled.bin.part").exists());
+
+    // a cancelled token stops the download before any request is sent
+    let result = downloader.download_to(&dir).await;
+    assert!(matches!(
+        result.map_err(DownloadError::into_inner),
+        Err(DownloadError::Cancelled { .. })
+    ));
+    assert_eq!(std::fs::read_dir(&dir)?.count(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_honours_overwrite_policy() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse::ok(payload())).await;
+    let dir = test_dir("overwrite");
+    let path = dir.join("existing.bin");
+
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(&path, b"old")?;
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/existing.bin", base))?;
+
+    downloader.with_overwrite_policy(OverwritePolicy::Skip);
+    let report = downloader.download_to(&dir).await?;
+    assert!(report.files()[0].skipped);
+    assert_eq!(report.total_size(), 3);
+    assert_eq!(std::fs::read(&path)?, b"old");
+
+    downloader.with_overwrite_policy(OverwritePolicy::Error);
+    let result = downloader.download_to(&dir).await;
+    assert!(
+        matches!(result.map_err(DownloadError::into_inner), Err(DownloadError::FileExists { path: p }) if p == path)
+    );
+
+    downloader.with_overwrite_policy(OverwritePolicy::Rename);
+    downloader.download_to(&dir).await?;
+    downloader.download_to(&dir).await?;
+    assert_eq!(std::fs::read(dir.join("existing (1).bin"))?, payload());
+    assert_eq!(std::fs::read(dir.join("existing (2).bin"))?, payload());
+    assert_eq!(std::fs::read(&path)?, b"old");
+
+    // the size of the existing file differs from the remote one
+    downloader.with_overwrite_policy(OverwritePolicy::SkipIfSameSize);
+    let report = downloader.download_to(&dir).await?;
+    assert!(!report.files()[0].skipped);
+    assert_eq!(std::fs::read(&path)?, payload());
+
+    let report = downloader.download_to(&dir).await?;
+    assert!(report.files()[0].skipped);
+
+    downloader.with_overwrite_policy(OverwritePolicy::Overwrite);
+    std::fs::write(&path, b"old")?;
+    downloader.download_to(&dir).await?;
+    assert_eq!(std::fs::read(&path)?, payload());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_saves_to_output_template() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse::ok(payload())).await;
+    let dir = test_dir("template");
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/archive.tar.gz", base))?;
+    downloader.with_output_template(OutputTemplate::parse(
+        "{host}/{{{title}}}-{index:03}.{ext}",
+    )?);
+
+    let report = downloader.download_to(&dir).await?;
+    let path = dir.join("127.0.0.1").join("{archive.tar}-NA.gz");
+
+    assert_eq!(report.files()[0].path, path);
+    assert_eq!(std::fs::read(&path)?, payload());
+
+    for template in [
+        "{title",
+        "title}",
+        "../{title}",
+        "/{title}",
+        "{ti tle}",
+        "{index:x}",
+        "",
+    ] {
+        assert!(matches!(
+            template.parse::<OutputTemplate>(),
+            Err(DownloadError::InvalidTemplate(_))
+        ));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_sanitizes_file_name() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse::ok(payload())).await;
+    let dir = test_dir("sanitize");
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/file.bin", base))?;
+    downloader.with_name("aux.txt".to_owned());
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("aux_.txt"))?, payload());
+
+    downloader.with_name("a very: long name.bin".to_owned());
+    downloader.with_file_name_sanitizer(
+        FileNameSanitizer::new()
+            .mode(SanitizeMode::Posix)
+            .max_length(12),
+    );
+    downloader.download_to(&dir).await?;
+
+    assert_eq!(std::fs::read(dir.join("a very:.bin"))?, payload());
+
+    let sanitizer = FileNameSanitizer::new().replacement(Some('-'));
+    assert_eq!(sanitizer.sanitize("what/is <this>?. "), "what-is -this--");
+    assert_eq!(sanitizer.sanitize(".."), "_");
+    assert_eq!(sanitizer.sanitize("CON"), "CON-");
+
+    let sanitizer = FileNameSanitizer::new().max_length(7);
+    assert_eq!(sanitizer.sanitize("ééééé.mp4"), "é.mp4");
+
+    let sanitizer = FileNameSanitizer::new().mode(SanitizeMode::StrictAscii);
+    assert_eq!(sanitizer.sanitize("Ünïcødé ★"), "Unicode _");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_info_from_head_request() -> Result<(), DownloadError> {
+    let base = serve(|request| TestResponse {
+        // only the headers are needed, the body must not be requested
+        status: if request.method == "HEAD" { 200 } else { 405 },
+        headers: vec![
+            ("Content-Type", "video/mp4".to_owned()),
+            ("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT".to_owned()),
+        ],
+        ..TestResponse::ok(payload())
+    })
+    .await;
+
+    let downloader = ResourceDownloader::new(&format!("{}/clip", base))?;
+    let info = downloader.info().await?;
+
+    assert_eq!(info.title.as_deref(), Some("clip"));
+    assert_eq!(info.upload_date.as_deref(), Some("20151021"));
+    assert_eq!(info.media.len(), 1);
+
+    let format = &info.media[0].formats[0];
+    assert_eq!(format.kind, FileKind::Video);
+    assert_eq!(format.ext.as_deref(), Some("mp4"));
+    assert_eq!(format.mime_type.as_deref(), Some("video/mp4"));
+    assert_eq!(format.size, Some(payload().len() as u64));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_writes_info_json() -> Result<(), DownloadError> {
+    let base = serve(|_| TestResponse {
+        headers: vec![
+            ("Content-Type", "application/pdf".to_owned()),
+            ("Last-Modified", "Sun, 06 Nov 1994 08:49:37 GMT".to_owned()),
+        ],
+        ..TestResponse::ok(payload())
+    })
+    .await;
+    let dir = test_dir("info_json");
+
+    let mut downloader = ResourceDownloader::new(&format!("{}/paper.pdf", base))?;
+    downloader.with_info_json(true);
+    downloader.download_to(&dir).await?;
+
+    let path = rusty_dl::info::InfoJson::path_for(&dir.join("paper.pdf"));
+    assert_eq!(path, dir.join("paper.info.json"));
+
+    let info_json: rusty_dl::info::InfoJson =
+        serde_json::from_slice(&std::fs::read(&path)?).map_err(std::io::Error::from)?;
+
+    assert_eq!(info_json.url, format!("{}/paper.pdf", base));
+    assert_eq!(info_json.extractor, "resource");
+    assert_eq!(info_json.title.as_deref(), Some("paper"));
+    assert_eq!(info_json.upload_date.as_deref(), Some("19941106"));
+    assert_eq!(info_json.file_name, "paper.pdf");
+    assert_eq!(info_json.size, payload().len() as u64);
+    assert!(info_json.downloaded_at > 0);
+
+    let format = info_json
+        .format
+        .expect("the format of the resource is known");
+    assert_eq!(format.mime_type.as_deref(), Some("application/pdf"));
+    assert_eq!(format.ext.as_deref(), Some("pdf"));
+
+    Ok(())
+}
+
+fn not_found(_: &TestRequest) -> TestResponse {
+    TestResponse {
+        status: 404,
+        ..TestResponse::ok(Vec::new())
+    }
+}
+
+#[test]
+fn resource_blocking_download_propagates_errors() -> Result<(), DownloadError> {
+    // the server runs on its own runtime, the blocking calls use the shared one
+    let server = tokio::runtime::Runtime::new()?;
+    let base = server.block_on(serve(|request| match request.path.as_str() {
+        "/blocking.bin" => TestResponse::ok(payload()),
+        _ => not_found(request),
+    }));
+    let dir = test_dir("blocking");
+
+    let downloader = ResourceDownloader::new(&format!("{}/blocking.bin", base))?;
+    let report = downloader.blocking_download_to(&dir)?;
+
+    assert_eq!(report.len(), 1);
+    assert_eq!(std::fs::read(dir.join("blocking.bin"))?, payload());
+
+    let downloader = ResourceDownloader::new(&format!("{}/missing.bin", base))?;
+    assert!(matches!(
+        downloader
+            .blocking_download_to(&dir)
+            .map_err(DownloadError::into_inner),
+        Err(DownloadError::HttpStatus { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn resource_blocking_download_inside_runtime() -> Result<(), DownloadError> {
+    let base = serve(not_found).await;
+    let dir = test_dir("blocking_runtime");
+
+    let downloader = ResourceDownloader::new(&format!("{}/missing.bin", base))?;
+    assert!(matches!(
+        downloader
+            .blocking_download_to(&dir)
+            .map_err(DownloadError::into_inner),
+        Err(DownloadError::HttpStatus { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resource_blocking_download_fails_on_current_thread_runtime() -> Result<(), DownloadError> {
+    let downloader = ResourceDownloader::new("http://127.0.0.1:9/never.bin")?;
+
+    assert!(matches!(
+        downloader.blocking_download_to(test_dir("blocking_current_thread")),
+        Err(DownloadError::FailedToBuildBlockingRuntime(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use reqwest::{Request, Response};
+use rusty_dl::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+const FILE_URL: &str = "https://fixtures.invalid/files/report.csv";
+
+/// Transport answering every request with the same body, the first `failures` requests failing with a network error.
+#[derive(Default)]
+struct FixtureTransport {
+    failures: usize,
+    attempts: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for FixtureTransport {
+    async fn execute(&self, _request: Request) -> Result<Response, DownloadError> {
+        if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+            return Err(DownloadError::new(
+                ErrorKind::Network,
+                "The connection was reset.",
+            ));
+        }
+
+        let body = b"id,name\n1,rusty\n".to_vec();
+        let response = http::Response::builder()
+            .status(200)
+            .header("Content-Length", body.len())
+            .body(body)
+            .unwrap();
+
+        Ok(Response::from(response))
+    }
+}
+
+/// A span or an event with its fields formatted with `Debug`.
+#[derive(Debug, Clone, Default)]
+struct Recorded {
+    name: String,
+    fields: BTreeMap<String, String>,
+}
+
+impl Visit for Recorded {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.fields
+            .insert(field.name().to_owned(), format!("{:?}", value));
+    }
+}
+
+/// Layer recording the spans in their order of creation, along with the values recorded after it, and the events.
+///
+/// The identifiers of closed spans are reused, the values being recorded in the last span created with the identifier.
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<(u64, Recorded)>>>,
+    events: Arc<Mutex<Vec<Recorded>>>,
+}
+
+impl Recorder {
+    fn spans(&self, name: &str) -> Vec<Recorded> {
+        let spans = self.spans.lock().unwrap();
+        spans
+            .iter()
+            .map(|(_, span)| span)
+            .filter(|span| span.name == name)
+            .cloned()
+            .collect()
+    }
+
+    fn messages(&self) -> Vec<String> {
+        let events = self.events.lock().unwrap();
+        events
+            .iter()
+            .filter_map(|event| event.fields.get("message").cloned())
+            .collect()
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Recorder {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+        let mut span = Recorded {
+            name: attrs.metadata().name().to_owned(),
+            ..Default::default()
+        };
+        attrs.record(&mut span);
+
+        self.spans.lock().unwrap().push((id.into_u64(), span));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        let mut spans = self.spans.lock().unwrap();
+        if let Some((_, span)) = spans
+            .iter_mut()
+            .rev()
+            .find(|(span_id, _)| *span_id == id.into_u64())
+        {
+            values.record(span);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut recorded = Recorded {
+            name: event.metadata().name().to_owned(),
+            ..Default::default()
+        };
+        event.record(&mut recorded);
+
+        self.events.lock().unwrap().push(recorded);
+    }
+}
+
+#[tokio::test]
+async fn downloads_are_instrumented() -> Result<(), DownloadError> {
+    let recorder = Recorder::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+    let dir = std::path::Path::new("./tests-run/tracing/instrumented");
+    let _ = std::fs::remove_dir_all(dir);
+
+    let mut downloader = ResourceDownloader::new(FILE_URL)?;
+    downloader
+        .with_transport(Transport::new(FixtureTransport {
+            failures: 1,
+            ..Default::default()
+        }))
+        .with_retry_policy(
+            RetryPolicy::new()
+                .max_attempts(3)
+                .base_delay(Duration::from_millis(1)),
+        );
+    downloader.download_to(dir).await?;
+
+    let downloads = recorder.spans("download");
+    assert_eq!(downloads.len(), 1);
+    assert_eq!(downloads[0].fields["extractor"], "\"resource\"");
+    assert_eq!(downloads[0].fields["url"], format!("{:?}", FILE_URL));
+
+    let items = recorder.spans("item");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].fields["item"], "0");
+    assert_eq!(items[0].fields["bytes"], "16");
+    assert!(items[0].fields.contains_key("elapsed_ms"));
+
+    let requests = recorder.spans("request");
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].fields["method"], "GET");
+    assert_eq!(requests[0].fields["url"], FILE_URL);
+    assert_eq!(requests[0].fields["attempt"], "1");
+    assert!(!requests[0].fields.contains_key("status"));
+    assert_eq!(requests[1].fields["attempt"], "2");
+    assert_eq!(requests[1].fields["status"], "200");
+
+    let messages = recorder.messages();
+    assert_eq!(messages, ["downloading", "retrying", "downloaded"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn failed_downloads_emit_a_warning() -> Result<(), DownloadError> {
+    let recorder = Recorder::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+    let mut downloader = ResourceDownloader::new(FILE_URL)?;
+    downloader
+        .with_transport(Transport::new(FixtureTransport {
+            failures: 1,
+            ..Default::default()
+        }))
+        .with_retry_policy(RetryPolicy::new().max_attempts(1));
+
+    let err = downloader
+        .download_to("./tests-run/tracing/failed")
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Network);
+
+    let events = recorder.events.lock().unwrap();
+    let failure = events.last().unwrap();
+    assert_eq!(failure.fields["message"], "download failed");
+    assert_eq!(failure.fields["error"], "The connection was reset.");
+    assert!(!recorder.spans("item")[0].fields.contains_key("bytes"));
+
+    Ok(())
+}
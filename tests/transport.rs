@@ -0,0 +1,456 @@
+use reqwest::{Client, Method, Proxy, Request, Response};
+use rusty_dl::extractor::YoutubeExtractor;
+use rusty_dl::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpListener;
+use url::Url;
+
+/// Recorded response served by the fixture transport.
+#[derive(Clone)]
+struct Fixture {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+/// Transport serving recorded responses by URL, without any network access.
+///
+/// Unknown URLs are answered with a `404`, and the first `failures` requests fail with a network error.
+/// A fixture whose URL ends with `?` answers that URL with any query, and the requests to `stalled` URLs never complete.
+#[derive(Clone, Default)]
+struct FixtureTransport {
+    fixtures: HashMap<String, Fixture>,
+    stalled: Vec<String>,
+    failures: usize,
+    requests: Arc<Mutex<Vec<(Method, String)>>>,
+    attempts: Arc<AtomicUsize>,
+}
+
+impl FixtureTransport {
+    fn with(mut self, url: &str, fixture: Fixture) -> Self {
+        self.fixtures.insert(url.to_owned(), fixture);
+        self
+    }
+
+    fn requests(&self) -> Vec<(Method, String)> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for FixtureTransport {
+    async fn execute(&self, request: Request) -> Result<Response, DownloadError> {
+        if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+            return Err(DownloadError::new(
+                ErrorKind::Network,
+                "The connection was reset.",
+            ));
+        }
+
+        let url = request.url().to_string();
+        self.requests
+            .lock()
+            .unwrap()
+            .push((request.method().clone(), url.clone()));
+
+        if self.stalled.contains(&url) {
+            std::future::pending::<()>().await;
+        }
+
+        let fixture = self.fixtures.get(&url).or_else(|| {
+            self.fixtures
+                .iter()
+                .find(|(key, _)| key.ends_with('?') && url.starts_with(key.as_str()))
+                .map(|(_, fixture)| fixture)
+        });
+        let fixture = fixture.cloned().unwrap_or(Fixture {
+            status: 404,
+            headers: Vec::new(),
+            body: b"Not Found".to_vec(),
+        });
+
+        let mut response = http::Response::builder()
+            .status(fixture.status)
+            .header("Content-Length", fixture.body.len());
+
+        for (name, value) in fixture.headers {
+            response = response.header(name, value);
+        }
+
+        // HEAD responses advertise the length of the body without sending it
+        let body = match request.method() {
+            &Method::HEAD => Vec::new(),
+            _ => fixture.body,
+        };
+
+        Ok(Response::from(response.body(body).unwrap()))
+    }
+}
+
+const FILE_URL: &str = "https://fixtures.invalid/files/report.csv";
+
+fn report() -> Fixture {
+    Fixture {
+        status: 200,
+        headers: vec![("Content-Type", "text/csv".to_owned())],
+        body: b"id,name\n1,rusty\n".to_vec(),
+    }
+}
+
+fn test_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::path::Path::new("./tests-run/transport/").join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[tokio::test]
+async fn transport_serves_recorded_responses() -> Result<(), DownloadError> {
+    let transport = FixtureTransport::default().with(FILE_URL, report());
+    let dir = test_dir("recorded");
+
+    let mut downloader = ResourceDownloader::new(FILE_URL)?;
+    downloader.with_transport(Transport::new(transport.clone()));
+
+    let info = downloader.info().await?;
+    assert_eq!(info.title.as_deref(), Some("report"));
+    assert_eq!(info.media[0].formats[0].size, Some(16));
+
+    let report = downloader.download_to(&dir).await?;
+    assert_eq!(report.len(), 1);
+    assert_eq!(
+        std::fs::read(dir.join("report.csv"))?,
+        b"id,name\n1,rusty\n"
+    );
+
+    let requests = transport.requests();
+    assert_eq!(requests[0], (Method::HEAD, FILE_URL.to_owned()));
+    assert!(requests[1..]
+        .iter()
+        .all(|request| *request == (Method::GET, FILE_URL.to_owned())));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn transport_network_errors_are_retried() -> Result<(), DownloadError> {
+    let transport = FixtureTransport {
+        failures: 2,
+        ..Default::default()
+    }
+    .with(FILE_URL, report());
+    let dir = test_dir("retried");
+
+    let mut downloader = ResourceDownloader::new(FILE_URL)?;
+    downloader
+        .with_transport(Transport::new(transport.clone()))
+        .with_retry_policy(
+            RetryPolicy::new()
+                .max_attempts(3)
+                .base_delay(Duration::from_millis(1)),
+        );
+
+    downloader.download_to(&dir).await?;
+    assert_eq!(
+        std::fs::read(dir.join("report.csv"))?,
+        b"id,name\n1,rusty\n"
+    );
+
+    let transport = FixtureTransport {
+        failures: 3,
+        ..Default::default()
+    }
+    .with(FILE_URL, report());
+    downloader.with_transport(Transport::new(transport));
+
+    let err = downloader
+        .download_to(test_dir("exhausted"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Network);
+    assert!(err.is_retryable());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn transport_errors_keep_their_status() -> Result<(), DownloadError> {
+    let transport = FixtureTransport::default();
+
+    let mut downloader = ExtractorRegistry::new().downloader(FILE_URL)?;
+    downloader.with_transport(Transport::new(transport.clone()));
+
+    let err = downloader.info().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert_eq!(err.extractor(), Some("resource"));
+    assert_eq!(transport.requests(), [(Method::HEAD, FILE_URL.to_owned())]);
+
+    Ok(())
+}
+
+const MAIN_JS: &str =
+    "http://mirror.invalid/assets/responsive-web/client-web-legacy/main.abc123.js";
+const TWEET_DETAILS: &str =
+    "http://mirror.invalid/twitter/i/api/graphql/ncDeACNGIApPMaqGVuF_rw/TweetResultByRestId?";
+
+fn page(body: &str) -> Fixture {
+    Fixture {
+        status: 200,
+        headers: Vec::new(),
+        body: body.as_bytes().to_vec(),
+    }
+}
+
+/// Returns a transport serving the pages giving the tokens of the tweet `1234567890` on `mirror.invalid`.
+fn tweet_transport() -> FixtureTransport {
+    FixtureTransport::default()
+        .with(
+            "http://mirror.invalid/twitter/user/status/1234567890",
+            page(&format!(r#"<script src="{}"></script>"#, MAIN_JS)),
+        )
+        .with(MAIN_JS, page(r#"const token="AAAAAAAAAbearer";"#))
+        .with(
+            "http://mirror.invalid/api/1.1/guest/activate.json",
+            page(r#"{"guest_token":"42"}"#),
+        )
+}
+
+/// Returns a downloader of the tweet `1234567890` sending its requests to `mirror.invalid` through the transport.
+fn tweet_downloader(transport: &FixtureTransport) -> Result<TwitterDownloader, DownloadError> {
+    let endpoints = TwitterEndpoints::new()
+        .web(Url::parse("http://mirror.invalid/twitter").unwrap())
+        .api(Url::parse("http://mirror.invalid/api/").unwrap())
+        .assets(Url::parse("http://mirror.invalid/assets").unwrap());
+
+    let mut downloader = TwitterDownloader::new("https://x.com/user/status/1234567890")?;
+    downloader
+        .with_endpoints(endpoints)
+        .with_transport(Transport::new(transport.clone()));
+
+    Ok(downloader)
+}
+
+#[tokio::test]
+async fn twitter_requests_go_to_its_endpoints() -> Result<(), DownloadError> {
+    let transport = tweet_transport();
+    let downloader = tweet_downloader(&transport)?;
+
+    // the details of the tweet are not recorded
+    let err = downloader.info().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(requests[1].1, MAIN_JS);
+    assert_eq!(requests[2].0, Method::POST);
+    assert!(requests[3].1.starts_with(TWEET_DETAILS));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancelled_tweet_returns_its_completed_medias() -> Result<(), DownloadError> {
+    let mut transport = tweet_transport()
+        .with(
+            TWEET_DETAILS,
+            page(include_str!("fixtures/tweet_details.json")),
+        )
+        .with(
+            "http://mirror.invalid/media/photo1.jpg",
+            page("first photo"),
+        );
+    transport
+        .stalled
+        .push("http://mirror.invalid/media/photo2.jpg".to_owned());
+
+    let token = CancellationToken::new();
+    let mut downloader = tweet_downloader(&transport)?;
+    downloader.with_cancellation(token.clone());
+
+    // the second photo never arrives, the download is cancelled once the first one is saved
+    downloader.on_progress(move |event| {
+        if let ProgressEvent::Finished { .. } = event {
+            token.cancel();
+        }
+    });
+
+    let dir = test_dir("cancelled_tweet");
+    let err = downloader.download_to(&dir).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Cancelled);
+
+    match err.into_inner() {
+        DownloadError::Cancelled { completed } => {
+            assert_eq!(completed.len(), 1);
+            assert_eq!(
+                completed.files()[0].url,
+                "http://mirror.invalid/media/photo1.jpg"
+            );
+            assert_eq!(std::fs::read(&completed.files()[0].path)?, b"first photo");
+        }
+        err => panic!("expected a cancellation, got {:?}", err),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn partially_failed_tweet_returns_its_completed_medias() -> Result<(), DownloadError> {
+    // the second photo is not recorded and answered with a `404`
+    let transport = tweet_transport()
+        .with(
+            TWEET_DETAILS,
+            page(include_str!("fixtures/tweet_details.json")),
+        )
+        .with(
+            "http://mirror.invalid/media/photo1.jpg",
+            page("first photo"),
+        );
+    let downloader = tweet_downloader(&transport)?;
+
+    let dir = test_dir("partial_tweet");
+    let err = downloader.download_to(&dir).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    match err.into_inner() {
+        DownloadError::Partial { completed, error } => {
+            assert_eq!(completed.len(), 1);
+            assert_eq!(std::fs::read(&completed.files()[0].path)?, b"first photo");
+            assert_eq!(error.url(), Some("http://mirror.invalid/media/photo2.jpg"));
+        }
+        err => panic!("expected a partial failure, got {:?}", err),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn medias_sharing_a_name_are_renamed() -> Result<(), DownloadError> {
+    let transport = tweet_transport()
+        .with(
+            TWEET_DETAILS,
+            page(include_str!("fixtures/tweet_details.json")),
+        )
+        .with(
+            "http://mirror.invalid/media/photo1.jpg",
+            page("first photo"),
+        )
+        .with(
+            "http://mirror.invalid/media/photo2.jpg",
+            page("second photo"),
+        );
+
+    // both photos are named after the tweet, and downloaded at the same time
+    let mut downloader = tweet_downloader(&transport)?;
+    downloader
+        .with_output_template(OutputTemplate::parse("{id}.{ext}")?)
+        .with_overwrite_policy(OverwritePolicy::Rename);
+
+    let dir = test_dir("renamed_medias");
+    let report = downloader.download_to(&dir).await?;
+
+    let mut paths = report.paths().map(Path::to_owned).collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(
+        paths,
+        [dir.join("1234567890 (1).jpg"), dir.join("1234567890.jpg")]
+    );
+
+    let mut contents = paths
+        .iter()
+        .map(std::fs::read)
+        .collect::<Result<Vec<_>, _>>()?;
+    contents.sort();
+    assert_eq!(
+        contents,
+        [b"first photo".to_vec(), b"second photo".to_vec()]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn youtube_pages_are_scraped_from_its_endpoints() -> Result<(), DownloadError> {
+    let transport = FixtureTransport::default();
+    let endpoints =
+        YoutubeEndpoints::new().web(Url::parse("http://mirror.invalid/youtube").unwrap());
+
+    let mut registry = ExtractorRegistry::empty();
+    registry.register(YoutubeExtractor::with_endpoints(endpoints));
+
+    let mut downloader = registry.downloader("https://www.youtube.com/playlist?list=PL123")?;
+    downloader.with_transport(Transport::new(transport.clone()));
+    assert!(downloader.info().await.is_err());
+
+    let mut downloader = registry.downloader("https://youtu.be/dQw4w9WgXcQ")?;
+    downloader.with_transport(Transport::new(transport.clone()));
+    assert!(downloader.download().await.is_err());
+
+    assert_eq!(
+        transport.requests(),
+        [
+            (
+                Method::GET,
+                "http://mirror.invalid/youtube/playlist?list=PL123".to_owned()
+            ),
+            (
+                Method::GET,
+                "http://mirror.invalid/youtube/watch?v=dQw4w9WgXcQ".to_owned()
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+/// Transport sending the requests with a client, which also sends the requests of `rusty_ytdl`.
+struct ClientTransport(Client);
+
+#[async_trait::async_trait]
+impl HttpTransport for ClientTransport {
+    async fn execute(&self, request: Request) -> Result<Response, DownloadError> {
+        Ok(self.0.execute(request).await?)
+    }
+
+    fn client(&self) -> Option<&Client> {
+        Some(&self.0)
+    }
+}
+
+#[tokio::test]
+async fn youtube_videos_are_fetched_with_the_client_of_the_transport() -> Result<(), DownloadError>
+{
+    // a proxy recording the first line of the requests it receives, then closing the connections
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let proxy = format!("http://{}", listener.local_addr()?);
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let lines = received.clone();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = [0u8; 1024];
+            let read = socket.read(&mut buf).await.unwrap_or(0);
+            let head = String::from_utf8_lossy(&buf[..read]);
+
+            lines
+                .lock()
+                .unwrap()
+                .push(head.lines().next().unwrap_or_default().to_owned());
+        }
+    });
+
+    let client = Client::builder().proxy(Proxy::all(&proxy)?).build()?;
+    let mut downloader = YoutubeDownloader::new("https://youtu.be/dQw4w9WgXcQ")?;
+    downloader.with_transport(Transport::new(ClientTransport(client)));
+    assert!(downloader.info().await.is_err());
+
+    let received = received.lock().unwrap();
+    assert!(received
+        .iter()
+        .any(|line| line.starts_with("CONNECT www.youtube.com:443")));
+
+    Ok(())
+}
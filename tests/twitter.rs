@@ -21,7 +21,7 @@ async fn twitter() -> Result<(), DownloadError> {
     .await;
 
     for result in results {
-        result?
+        result?;
     }
 
     println!("it took {} seconds!", start.elapsed().as_secs_f64());
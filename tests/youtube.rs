@@ -33,7 +33,7 @@ async fn youtube() -> Result<(), DownloadError> {
     .await;
 
     for result in results {
-        result?
+        result?;
     }
 
     assert_folder_len("./tests-run/videos/", 2)?;